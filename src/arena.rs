@@ -1,12 +1,18 @@
+use std::collections::{HashMap, HashSet};
+
 use ratatui::Frame;
 use slotmap::{SlotMap, new_key_type};
 
 use crate::{
     canvas::Canvas,
-    component::{Node, NodeAttributes},
+    component::{Child, Component, ErrorInfo, Node, NodeAttributes},
     context::{Context, EventContext},
+    errors::{BoxError, RuntimeError},
     layout::{LogicalRect, Measure, compute_split},
-    transport::Event,
+    listeners::Listeners,
+    panic,
+    state::{self, StateId},
+    transport::{Event, Propagation},
 };
 
 /// Stores UI nodes in memory and dispatches requests to them.
@@ -14,6 +20,8 @@ pub struct Arena {
     root: NodeId,
     nodes: SlotMap<NodeId, ArenaNode>,
     traversal: Vec<NodeId>,
+    faults: Vec<(NodeId, String)>,
+    errors: Vec<RuntimeError>,
 }
 
 impl From<Node> for Arena {
@@ -21,7 +29,7 @@ impl From<Node> for Arena {
         let mut nodes = SlotMap::default();
         let root = nodes.insert(root.into());
 
-        remount_subtree(&mut nodes, root);
+        reconcile_subtree(&mut nodes, root);
 
         let traversal = compute_traversal(&nodes, root);
 
@@ -29,12 +37,17 @@ impl From<Node> for Arena {
             root,
             nodes,
             traversal,
+            faults: Vec::new(),
+            errors: Vec::new(),
         }
     }
 }
 
 impl Arena {
     /// Draws the node tree on the given frame.
+    ///
+    /// Panics within an error boundary are recorded and handled by [`Arena::recover`]. Errors
+    /// returned by render functions are collected by [`Arena::take_errors`].
     pub fn render(&mut self, frame: &mut Frame, context: &Context) {
         compute_layout(&mut self.nodes, self.root, frame.area().into());
 
@@ -44,20 +57,129 @@ impl Arena {
             let node = &self.nodes[id];
             let mut canvas = Canvas::new(buf, context, id, node);
 
-            node.render(&mut canvas);
+            let result = match self.boundary_of(id) {
+                Some(boundary) => {
+                    panic::catch_panic(|| node.render(&mut canvas)).unwrap_or_else(|message| {
+                        self.faults.push((boundary, message));
+                        Ok(())
+                    })
+                }
+                None => node.render(&mut canvas),
+            };
+
+            if let Err(source) = result {
+                let path = self.path_of(id);
+                self.errors.push(RuntimeError::Component { path, source });
+            }
         }
     }
 
-    /// Broadcasts an event to the node tree.
+    /// Dispatches an event to the node tree according to its [`Propagation`].
+    ///
+    /// Capturing listeners run first, from parents to children, followed by regular listeners in
+    /// the opposite order. Either phase ends early if propagation is stopped.
     pub fn update<E: Event>(&mut self, event: &E, context: &mut Context) {
-        let target = event.target(self);
-        context.set_target(target);
+        let target = event
+            .target(self, context)
+            .filter(|&id| self.nodes.contains_key(id));
+        context.set_target(target.map(|id| (id, self.nodes[id].rect)));
+
+        let (capture, bubble) = self.dispatch_order(target, event.propagation());
+
+        for id in capture {
+            self.notify(id, event, context, Node::capture_listeners_mut);
+
+            if context.propagation_stopped() {
+                return;
+            }
+        }
+
+        for id in bubble {
+            self.notify(id, event, context, Node::listeners_mut);
+
+            if context.propagation_stopped() {
+                return;
+            }
+        }
+    }
+
+    /// Replaces the children of error boundaries whose descendants panicked with their fallback.
+    ///
+    /// A boundary whose fallback panics passes the error on to the next boundary. Panics again if
+    /// no boundary is left to handle an error.
+    ///
+    /// Returns `true` if the node tree changed.
+    pub fn recover(&mut self) -> bool {
+        if self.faults.is_empty() {
+            return false;
+        }
+
+        for (mut boundary, message) in core::mem::take(&mut self.faults) {
+            if !self.nodes.contains_key(boundary) {
+                continue;
+            }
+
+            while self.nodes[boundary].fault.is_some() {
+                match self.boundary_of(boundary) {
+                    Some(parent) => boundary = parent,
+                    None => panic!("{message}"),
+                }
+            }
+
+            self.nodes[boundary].fault = Some(message);
+            reconcile_subtree(&mut self.nodes, boundary);
+        }
+
+        self.traversal = compute_traversal(&self.nodes, self.root);
+
+        true
+    }
+
+    /// Returns the errors returned by components since the last call.
+    pub fn take_errors(&mut self) -> Vec<RuntimeError> {
+        core::mem::take(&mut self.errors)
+    }
+
+    /// Re-runs composition for nodes which read any of the given states.
+    ///
+    /// Returns `true` if the node tree changed.
+    pub fn recompose(&mut self, writes: &HashSet<StateId>) -> bool {
+        if writes.is_empty() {
+            return false;
+        }
+
+        let stale: Vec<NodeId> = self
+            .traversal
+            .iter()
+            .copied()
+            .filter(|&id| self.nodes[id].deps.iter().any(|dep| writes.contains(dep)))
+            .collect();
+
+        if stale.is_empty() {
+            return false;
+        }
+
+        // Ancestors come first in traversal order and reconcile their whole subtree, so stale
+        // descendants of a reconciled node are skipped whether they were removed or reused
+        let mut reconciled = HashSet::new();
+
+        for id in stale {
+            let covered = self.ancestors(id).any(|id| reconciled.contains(&id));
 
-        for &id in self.traversal.iter().rev() {
-            let node = &mut self.nodes[id];
-            let mut ctx = EventContext::new(event, context, id, node);
-            node.node.listeners_mut().dispatch(&mut ctx);
+            if self.nodes.contains_key(id) && !covered {
+                reconcile_subtree(&mut self.nodes, id);
+                reconciled.insert(id);
+            }
         }
+
+        self.traversal = compute_traversal(&self.nodes, self.root);
+
+        true
+    }
+
+    /// Returns the root node.
+    pub fn root(&self) -> NodeId {
+        self.root
     }
 
     /// Returns a reference to a node.
@@ -65,6 +187,13 @@ impl Arena {
         self.nodes.get(id)
     }
 
+    /// Returns an iterator over a node and its ancestors, ending at the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> {
+        core::iter::successors(Some(id), |&id| {
+            self.nodes.get(id).and_then(|node| node.parent)
+        })
+    }
+
     /// Returns an iterator in the forward traversal order.
     pub fn traverse(&self) -> impl DoubleEndedIterator<Item = (NodeId, &ArenaNode)> {
         self.traversal.iter().map(|&id| {
@@ -73,6 +202,89 @@ impl Arena {
         })
     }
 
+    /// Returns the nearest error boundary above a node.
+    fn boundary_of(&self, id: NodeId) -> Option<NodeId> {
+        self.ancestors(id)
+            .skip(1)
+            .find(|&id| self.nodes[id].node.fallback().is_some())
+    }
+
+    /// Returns the position of a node among its siblings at each level of the tree.
+    fn path_of(&self, id: NodeId) -> String {
+        let mut indices: Vec<usize> = self
+            .ancestors(id)
+            .filter_map(|id| {
+                let parent = self.nodes[id].parent?;
+                self.nodes[parent].children.iter().position(|&c| c == id)
+            })
+            .collect();
+
+        if indices.is_empty() {
+            return String::from("/");
+        }
+
+        indices.reverse();
+        indices.iter().map(|index| format!("/{index}")).collect()
+    }
+
+    /// Runs the listeners of a node, recording a fault if they panic within an error boundary.
+    fn notify<E: Event>(
+        &mut self,
+        id: NodeId,
+        event: &E,
+        context: &mut Context,
+        listeners: fn(&mut Node) -> &mut Listeners,
+    ) {
+        let boundary = self.boundary_of(id);
+        let node = &mut self.nodes[id];
+        let mut ctx = EventContext::new(event, context, id, node);
+        let listeners = listeners(&mut node.node);
+
+        let result = match boundary {
+            Some(boundary) => {
+                panic::catch_panic(|| listeners.dispatch(&mut ctx)).unwrap_or_else(|message| {
+                    self.faults.push((boundary, message));
+                    Ok(())
+                })
+            }
+            None => listeners.dispatch(&mut ctx),
+        };
+
+        if let Err(source) = result {
+            let path = self.path_of(id);
+            self.errors.push(RuntimeError::Component { path, source });
+        }
+    }
+
+    /// Returns the capturing and bubbling dispatch orders for an event.
+    fn dispatch_order(
+        &self,
+        target: Option<NodeId>,
+        propagation: Propagation,
+    ) -> (Vec<NodeId>, Vec<NodeId>) {
+        let path: Vec<NodeId> = match target {
+            Some(id) => self.ancestors(id).collect(),
+            None => Vec::new(),
+        };
+
+        match propagation {
+            Propagation::Broadcast => {
+                let capture = self.traversal.clone();
+                let rest = self.traversal.iter().rev().filter(|id| !path.contains(id));
+                let bubble = path.iter().chain(rest).copied().collect();
+                (capture, bubble)
+            }
+            Propagation::Bubble => {
+                let capture = path.iter().rev().copied().collect();
+                (capture, path)
+            }
+            Propagation::Direct => {
+                let path: Vec<NodeId> = target.into_iter().collect();
+                (path.clone(), path)
+            }
+        }
+    }
+
     /// Sets the render offset of a node.
     ///
     /// # Panics
@@ -105,7 +317,10 @@ new_key_type! { pub struct NodeId; }
 pub struct ArenaNode {
     node: Node,
     rect: LogicalRect,
+    parent: Option<NodeId>,
     children: Vec<NodeId>,
+    deps: Vec<StateId>,
+    fault: Option<String>,
 }
 
 impl From<Node> for ArenaNode {
@@ -113,7 +328,10 @@ impl From<Node> for ArenaNode {
         Self {
             node,
             rect: LogicalRect::zeroed(),
+            parent: None,
             children: Vec::new(),
+            deps: Vec::new(),
+            fault: None,
         }
     }
 }
@@ -128,9 +346,10 @@ impl ArenaNode {
     }
 
     /// Renders the component into the frame buffer.
-    fn render(&self, canvas: &mut Canvas) {
-        if let Some(renderer) = &self.node.renderer() {
-            renderer(canvas);
+    fn render(&self, canvas: &mut Canvas) -> Result<(), BoxError> {
+        match self.node.renderer() {
+            Some(renderer) => renderer(canvas),
+            None => Ok(()),
         }
     }
 }
@@ -184,16 +403,79 @@ fn compute_traversal(nodes: &SlotMap<NodeId, ArenaNode>, root: NodeId) -> Vec<No
     order
 }
 
-/// Destroys and recreates the children of the given [`Node`].
-fn remount_subtree(nodes: &mut SlotMap<NodeId, ArenaNode>, root_id: NodeId) {
-    remove_subtree(nodes, root_id);
+/// Composes the children of the given [`Node`], reusing existing children with matching identities.
+///
+/// Keyed children are matched by key, while unkeyed children are matched by their position among
+/// unkeyed siblings. Reused children keep their [`NodeId`] and state but are rebuilt with new props.
+///
+/// Error boundaries which caught a panic are composed with only their fallback.
+fn reconcile_subtree(nodes: &mut SlotMap<NodeId, ArenaNode>, root_id: NodeId) {
+    let fallback = nodes[root_id].node.fallback();
+
+    let (children, deps) = match (&nodes[root_id].fault, fallback) {
+        (Some(message), Some(fallback)) => {
+            let info = ErrorInfo {
+                message: message.clone(),
+            };
+            (vec![Child::new(fallback, info)], Vec::new())
+        }
+        _ => state::track_reads(|| nodes[root_id].node.compose()),
+    };
+
+    nodes[root_id].deps = deps;
+
+    let old = core::mem::take(&mut nodes[root_id].children);
+    let mut keyed = HashMap::new();
+    let mut unkeyed = Vec::new();
+    let mut duplicates = Vec::new();
+
+    for id in old {
+        match nodes[id].node.identity() {
+            Some(identity) if identity.is_keyed() => {
+                if let Some(prev) = keyed.insert(identity, id) {
+                    duplicates.push(prev);
+                }
+            }
+            _ => unkeyed.push(Some(id)),
+        }
+    }
 
-    let children = nodes[root_id].node.compose();
+    let mut unkeyed_idx = 0;
 
     for child in children {
-        let child_id = nodes.insert(child.into());
+        let identity = child.identity();
+
+        let reused = if identity.is_keyed() {
+            keyed.remove(&identity)
+        } else {
+            let slot = unkeyed.get_mut(unkeyed_idx);
+            unkeyed_idx += 1;
+            slot.and_then(|slot| slot.take_if(|id| nodes[*id].node.identity() == Some(identity)))
+        };
+
+        let child_id = match reused {
+            Some(id) => {
+                let state = nodes[id].node.take_state();
+                let offset = nodes[id].node.attributes().offset;
+
+                let mut node = child.build(Component::with_state(state));
+                node.attributes_mut().offset = offset;
+                nodes[id].node = node;
+                id
+            }
+            None => nodes.insert(child.build(Component::new()).into()),
+        };
+
+        nodes[child_id].parent = Some(root_id);
         nodes[root_id].children.push(child_id);
-        remount_subtree(nodes, child_id);
+        reconcile_subtree(nodes, child_id);
+    }
+
+    let unmatched = keyed.into_values().chain(unkeyed.into_iter().flatten());
+
+    for id in unmatched.chain(duplicates) {
+        remove_subtree(nodes, id);
+        nodes.remove(id);
     }
 }
 
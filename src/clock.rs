@@ -0,0 +1,32 @@
+use alloc::rc::Rc;
+use core::{cell::Cell, time::Duration};
+use std::time::Instant;
+
+/// The source of the current time for timers and the runtime loop.
+///
+/// Follows the system clock by default. A manual clock only moves when advanced, which makes
+/// timers deterministic in tests.
+#[derive(Clone, Default)]
+pub struct Clock(Option<Rc<Cell<Instant>>>);
+
+impl Clock {
+    /// Creates a clock which starts at the current time and only moves when advanced.
+    pub fn manual() -> Self {
+        Self(Some(Rc::new(Cell::new(Instant::now()))))
+    }
+
+    /// Returns the current time.
+    pub fn now(&self) -> Instant {
+        match &self.0 {
+            Some(now) => now.get(),
+            None => Instant::now(),
+        }
+    }
+
+    /// Moves a manual clock forward. Has no effect on a clock following the system clock.
+    pub fn advance(&self, duration: Duration) {
+        if let Some(now) = &self.0 {
+            now.set(now.get() + duration);
+        }
+    }
+}
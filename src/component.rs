@@ -1,15 +1,21 @@
-use core::cell::RefCell;
+use core::{
+    any::TypeId,
+    cell::RefCell,
+    hash::{Hash, Hasher},
+};
+use std::hash::DefaultHasher;
 
 use crate::{
     canvas::Canvas,
     context::EventContext,
+    errors::BoxError,
     layout::{Flow, Inset, Measure, Placement},
     listeners::Listeners,
     state::{State, StateStore},
     transport::Event,
 };
 
-pub type BoxedRenderer = Box<dyn Fn(&mut Canvas)>;
+pub type BoxedRenderer = Box<dyn Fn(&mut Canvas) -> Result<(), BoxError>>;
 pub type Factory<P = ()> = fn(Component, P) -> Node;
 
 /// Types that are used when initializing a [`Node`].
@@ -17,6 +23,17 @@ pub trait Props: Clone + 'static {}
 
 impl Props for () {}
 
+/// Props given to the fallback of an error boundary.
+///
+/// See [`Component::catch_errors`].
+#[derive(Debug, Clone)]
+pub struct ErrorInfo {
+    /// The message of the panic which was caught.
+    pub message: String,
+}
+
+impl Props for ErrorInfo {}
+
 /// An UI element with rendering and event listening behavior.
 pub struct Component {
     node: RefCell<Node>,
@@ -65,6 +82,16 @@ impl Component {
 
     /// Defines a render function for this component.
     pub fn draw(&self, renderer: impl Fn(&mut Canvas) + 'static) {
+        self.try_draw(move |canvas| {
+            renderer(canvas);
+            Ok(())
+        });
+    }
+
+    /// Defines a fallible render function for this component.
+    ///
+    /// Errors are reported as a [`RuntimeError::Component`](crate::RuntimeError::Component).
+    pub fn try_draw(&self, renderer: impl Fn(&mut Canvas) -> Result<(), BoxError> + 'static) {
         let renderer = Box::new(renderer);
         self.node.borrow_mut().draw_fn = Some(renderer);
     }
@@ -73,21 +100,77 @@ impl Component {
     ///
     /// Listeners are sequential and single-threaded on the runtime thread. It is important to
     /// never run any blocking tasks to avoid UI jitters.
-    pub fn listen<E>(&self, callback: impl FnMut(&mut EventContext<E>) + 'static)
+    pub fn listen<E>(&self, mut callback: impl FnMut(&mut EventContext<E>) + 'static)
     where
         E: Event,
+    {
+        self.try_listen(move |event: &mut EventContext<E>| {
+            callback(event);
+            Ok(())
+        });
+    }
+
+    /// Adds a fallible listener for an event.
+    ///
+    /// Errors are reported as a [`RuntimeError::Component`](crate::RuntimeError::Component), and
+    /// the remaining listeners of this component are skipped for the event.
+    pub fn try_listen<E>(
+        &self,
+        callback: impl FnMut(&mut EventContext<E>) -> Result<(), BoxError> + 'static,
+    ) where
+        E: Event,
     {
         self.node.borrow_mut().listeners.push(callback);
     }
 
+    /// Adds a capturing listener for an event.
+    ///
+    /// Capturing listeners run from parents to children before any listener added with
+    /// [`Component::listen`], allowing a parent to intercept an event before its children.
+    pub fn listen_capture<E>(&self, mut callback: impl FnMut(&mut EventContext<E>) + 'static)
+    where
+        E: Event,
+    {
+        self.try_listen_capture(move |event: &mut EventContext<E>| {
+            callback(event);
+            Ok(())
+        });
+    }
+
+    /// Adds a fallible capturing listener for an event.
+    ///
+    /// See [`Component::listen_capture`] and [`Component::try_listen`].
+    pub fn try_listen_capture<E>(
+        &self,
+        callback: impl FnMut(&mut EventContext<E>) -> Result<(), BoxError> + 'static,
+    ) where
+        E: Event,
+    {
+        self.node.borrow_mut().capture_listeners.push(callback);
+    }
+
+    /// Makes this component an error boundary.
+    ///
+    /// If a descendant panics while drawing or handling an event, the children of this component
+    /// are removed and replaced with a single `fallback` child describing the panic.
+    pub fn catch_errors(&self, fallback: Factory<ErrorInfo>) {
+        self.node.borrow_mut().fallback = Some(fallback);
+    }
+
     /// Initializes component state.
     ///
-    /// State is owned by the component and can be passed down to children.
+    /// State is owned by the component and can be passed down to children. When a parent
+    /// recomposes, a retained child keeps its existing state and `value` is ignored. States must
+    /// therefore be initialized unconditionally and in the same order every time.
     pub fn state<T: 'static>(&self, value: T) -> State<T> {
         self.node.borrow_mut().state.insert(value)
     }
 
     /// Builds the component into a [`Node`].
+    ///
+    /// The builder is re-run whenever a [`State`] it reads changes. The new children are then
+    /// reconciled with the existing ones, which keep their state when matched by position or by
+    /// a key set with [`UiNode::key`].
     pub fn compose<F>(self, builder: F) -> Node
     where
         F: Fn(&mut Ui) + 'static,
@@ -101,6 +184,14 @@ impl Component {
         let node = RefCell::new(Node::new());
         Self { node }
     }
+
+    /// Creates a component which reuses the states of a previous build.
+    pub(crate) fn with_state(state: StateStore) -> Self {
+        let mut node = Node::new();
+        node.state = state.rewind();
+        let node = RefCell::new(node);
+        Self { node }
+    }
 }
 
 /// A compiled description of an application's UI tree.
@@ -109,7 +200,10 @@ pub struct Node {
     state: StateStore,
     draw_fn: Option<BoxedRenderer>,
     listeners: Listeners,
+    capture_listeners: Listeners,
     ui: Box<dyn Fn(&mut Ui)>,
+    identity: Option<Identity>,
+    fallback: Option<Factory<ErrorInfo>>,
 }
 
 impl From<Component> for Node {
@@ -126,7 +220,10 @@ impl Node {
             state: StateStore::default(),
             draw_fn: Option::default(),
             listeners: Listeners::default(),
+            capture_listeners: Listeners::default(),
             ui: Box::new(|_| {}),
+            identity: None,
+            fallback: None,
         }
     }
 
@@ -139,12 +236,27 @@ impl Node {
         &mut self.attributes
     }
 
-    pub(crate) fn compose(&self) -> Vec<Node> {
+    pub(crate) fn compose(&self) -> Vec<Child> {
         let mut ui = Ui::default();
         (self.ui)(&mut ui);
         ui.0
     }
 
+    /// Returns the identity used to match this node across compositions.
+    pub(crate) fn identity(&self) -> Option<Identity> {
+        self.identity
+    }
+
+    /// Returns the fallback factory if this node is an error boundary.
+    pub(crate) fn fallback(&self) -> Option<Factory<ErrorInfo>> {
+        self.fallback
+    }
+
+    /// Moves the state out of this node so it can be given to a rebuilt node.
+    pub(crate) fn take_state(&mut self) -> StateStore {
+        core::mem::take(&mut self.state)
+    }
+
     /// Returns the flow of this node.
     pub(crate) fn flow(&self) -> Flow {
         self.attributes.flow
@@ -155,6 +267,11 @@ impl Node {
         &mut self.listeners
     }
 
+    /// Returns the capturing listeners of this node.
+    pub(crate) fn capture_listeners_mut(&mut self) -> &mut Listeners {
+        &mut self.capture_listeners
+    }
+
     /// Returns the draw function of this node.
     pub(crate) fn renderer(&self) -> Option<&BoxedRenderer> {
         self.draw_fn.as_ref()
@@ -174,16 +291,83 @@ pub struct NodeAttributes {
     pub padding: Inset,
 }
 
+/// Identifies a child across compositions of its parent.
+///
+/// The factory is compared by address, which is not guaranteed to be unique or stable, as noted
+/// on [`Ui::child`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Identity {
+    factory: usize,
+    props: TypeId,
+    key: Option<u64>,
+}
+
+impl Identity {
+    /// Determines if this identity has an explicit key.
+    pub(crate) fn is_keyed(&self) -> bool {
+        self.key.is_some()
+    }
+}
+
+/// A child which has been described during composition but not yet built.
+pub(crate) struct Child {
+    identity: Identity,
+    factory: Box<dyn FnOnce(Component) -> Node>,
+    measure: Option<Measure>,
+}
+
+impl Child {
+    /// Describes a child built from a factory and its props.
+    pub(crate) fn new<P: Props>(factory: Factory<P>, props: P) -> Self {
+        let identity = Identity {
+            factory: factory as usize,
+            props: TypeId::of::<P>(),
+            key: None,
+        };
+
+        Self {
+            identity,
+            factory: Box::new(move |component| factory(component, props)),
+            measure: None,
+        }
+    }
+
+    /// Returns the identity used to match this child against existing nodes.
+    pub(crate) fn identity(&self) -> Identity {
+        self.identity
+    }
+
+    /// Builds this child into a [`Node`] using the given component.
+    pub(crate) fn build(self, component: Component) -> Node {
+        let mut node = (self.factory)(component);
+
+        if let Some(measure) = self.measure {
+            node.attributes.measure = measure;
+        }
+
+        node.identity = Some(self.identity);
+        node
+    }
+}
+
 /// A builder for adding children to a component during composition.
 ///
 /// Passed to the closure in [`Component::compose`] to construct the component's subtree.
 #[derive(Default)]
-pub struct Ui(Vec<Node>);
+pub struct Ui(Vec<Child>);
 
 impl Ui {
     /// Adds a new child to this node.
+    ///
+    /// When the parent recomposes, children are matched to existing nodes by their factory and
+    /// position, or by key if one is set with [`UiNode::key`].
+    ///
+    /// Factories are compared by function address. The compiler may merge identical functions
+    /// into one, or give a function different addresses in different codegen units, so children
+    /// of identical factories can be mistaken for each other, and a child can rarely lose its
+    /// state. Factories with distinct bodies in the same crate are not affected in practice.
     pub fn child<P: Props>(&mut self, factory: Factory<P>, props: P) -> UiNode<'_> {
-        self.0.push(factory(Component::new(), props));
+        self.0.push(Child::new(factory, props));
         UiNode(self, self.0.len() - 1)
     }
 }
@@ -196,11 +380,22 @@ pub struct UiNode<'ui>(&'ui mut Ui, usize);
 impl UiNode<'_> {
     /// Sets the [`Measure`] of this node.
     pub fn measure(&mut self, measure: Measure) -> &mut Self {
-        self.node_mut().attributes_mut().measure = measure;
+        self.child_mut().measure = Some(measure);
+        self
+    }
+
+    /// Sets the key of this node.
+    ///
+    /// Keyed children keep their state and focus when their position among siblings changes.
+    /// Keys should be unique among the siblings created by the same factory.
+    pub fn key(&mut self, key: impl Hash) -> &mut Self {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.child_mut().identity.key = Some(hasher.finish());
         self
     }
 
-    fn node_mut(&mut self) -> &mut Node {
+    fn child_mut(&mut self) -> &mut Child {
         (self.0)
             .0
             .get_mut(self.1)
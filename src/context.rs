@@ -1,66 +1,110 @@
-use core::ops::Deref;
+use alloc::collections::VecDeque;
+use core::{any::Any, ops::Deref, time::Duration};
+use std::time::Instant;
 
+#[cfg(feature = "tokio")]
+use crate::executor::Executor;
 use crate::{
     arena::{Arena, ArenaNode, NodeId},
+    clock::Clock,
     component::NodeAttributes,
+    drivers::Capabilities,
     events::FocusChanged,
     layout::{LogicalRect, Measure},
-    transport::{Event, MessageSender, MouseEvent},
+    timers::{TimerHandle, Timers},
+    transport::{AppHandle, Event, Message, MessageSender, MouseEvent},
 };
 
 pub enum Command {
     Shutdown,
+    Exit(Box<dyn Any>),
+    Suspend(Box<dyn FnOnce()>),
     SetOffset(NodeId, i32, i32),
     SetMeasure(NodeId, Measure),
     SetFocus(NodeId),
     ResignFocus(NodeId),
     Tick,
+    Redraw,
 }
 
 impl Command {
     pub fn reduce(self, ctx: &mut Context, arena: &mut Arena) {
         match self {
             Self::Shutdown => ctx.shutdown_requested = true,
-            Self::SetOffset(id, x, y) => arena.set_offset(id, x, y),
-            Self::SetMeasure(id, measure) => arena.set_measure(id, measure),
+            Self::Exit(value) => {
+                ctx.exit_value.get_or_insert(value);
+                ctx.shutdown_requested = true;
+            }
+            Self::Suspend(f) => ctx.suspensions.push(f),
+            Self::SetOffset(id, x, y) => {
+                arena.set_offset(id, x, y);
+                ctx.redraw_requested = true;
+            }
+            Self::SetMeasure(id, measure) => {
+                arena.set_measure(id, measure);
+                ctx.redraw_requested = true;
+            }
             Self::SetFocus(id) => {
                 if let Some(node) = arena.get(id)
                     && node.attributes().focusable
                     && ctx.focused != Some(id)
                 {
                     ctx.focused = Some(id);
-                    let _ = ctx.handle().send(FocusChanged {});
+                    ctx.redraw_requested = true;
+                    ctx.post(FocusChanged {});
                 }
             }
             Self::ResignFocus(id) => {
                 if ctx.focused == Some(id) {
                     ctx.focused = None;
-                    let _ = ctx.handle().send(FocusChanged {});
+                    ctx.redraw_requested = true;
+                    ctx.post(FocusChanged {});
                 }
             }
             Self::Tick => ctx.tick_requested = true,
+            Self::Redraw => ctx.redraw_requested = true,
         }
     }
 }
 
 pub struct Context {
     handle: MessageSender,
-    target: Option<NodeId>,
+    posted: VecDeque<Message>,
+    target: Option<(NodeId, LogicalRect)>,
     focused: Option<NodeId>,
     command_buffer: Vec<Command>,
     tick_requested: bool,
+    redraw_requested: bool,
     shutdown_requested: bool,
+    exit_value: Option<Box<dyn Any>>,
+    suspensions: Vec<Box<dyn FnOnce()>>,
+    propagation_stopped: bool,
+    capabilities: Capabilities,
+    clock: Clock,
+    timers: Timers,
+    #[cfg(feature = "tokio")]
+    executor: Executor,
 }
 
 impl Context {
     pub fn new(handle: MessageSender) -> Self {
         Self {
             handle,
+            posted: VecDeque::new(),
             target: None,
             focused: None,
             command_buffer: Vec::default(),
             tick_requested: false,
+            redraw_requested: true,
             shutdown_requested: false,
+            exit_value: None,
+            suspensions: Vec::new(),
+            propagation_stopped: false,
+            capabilities: Capabilities::empty(),
+            clock: Clock::default(),
+            timers: Timers::default(),
+            #[cfg(feature = "tokio")]
+            executor: Executor::default(),
         }
     }
 
@@ -76,8 +120,29 @@ impl Context {
         &self.handle
     }
 
-    pub fn set_target(&mut self, target: Option<NodeId>) {
+    /// Queues an event raised by the runtime itself.
+    ///
+    /// The runtime thread is the only receiver of the bus, so it must never wait for space in it.
+    /// Posted events are handled before those in the bus.
+    pub fn post(&mut self, msg: impl Into<Message>) {
+        self.posted.push_back(msg.into());
+    }
+
+    pub fn take_posted(&mut self) -> Option<Message> {
+        self.posted.pop_front()
+    }
+
+    pub fn target(&self) -> Option<NodeId> {
+        self.target.map(|(id, _)| id)
+    }
+
+    pub fn set_target(&mut self, target: Option<(NodeId, LogicalRect)>) {
         self.target = target;
+        self.propagation_stopped = false;
+    }
+
+    pub fn propagation_stopped(&self) -> bool {
+        self.propagation_stopped
     }
 
     pub fn tick_requested(&self) -> bool {
@@ -88,10 +153,59 @@ impl Context {
         self.tick_requested = false;
     }
 
+    pub fn redraw_requested(&self) -> bool {
+        self.redraw_requested
+    }
+
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    pub fn clear_redraw_request(&mut self) {
+        self.redraw_requested = false;
+    }
+
     pub fn shutdown_requested(&self) -> bool {
         self.shutdown_requested
     }
 
+    pub fn take_exit_value(&mut self) -> Option<Box<dyn Any>> {
+        self.exit_value.take()
+    }
+
+    pub fn take_suspensions(&mut self) -> Vec<Box<dyn FnOnce()>> {
+        core::mem::take(&mut self.suspensions)
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn timers(&self) -> &Timers {
+        &self.timers
+    }
+
+    pub fn timers_mut(&mut self) -> &mut Timers {
+        &mut self.timers
+    }
+
+    #[cfg(feature = "tokio")]
+    pub fn executor_mut(&mut self) -> &mut Executor {
+        &mut self.executor
+    }
+
     pub fn enqueue(&mut self, cmd: Command) {
         self.command_buffer.push(cmd);
     }
@@ -182,6 +296,66 @@ impl<E: Event> EventContext<'_, E> {
         self.context.enqueue(Command::Tick);
     }
 
+    /// Schedules a [`Timer`](crate::events::Timer) event for this component after a delay.
+    ///
+    /// The timer is cancelled if this component is removed from the tree.
+    pub fn set_timeout(&mut self, delay: Duration) -> TimerHandle {
+        let now = self.context.now();
+        self.context
+            .timers_mut()
+            .schedule(self.current_node, now, delay, None)
+    }
+
+    /// Schedules a [`Timer`](crate::events::Timer) event for this component at a fixed period.
+    ///
+    /// The first event fires after one period. Periods shorter than a millisecond are rounded up
+    /// to one. If updates fall behind, missed periods are skipped and the timer fires once. The
+    /// timer is cancelled if this component is removed from the tree.
+    pub fn set_interval(&mut self, period: Duration) -> TimerHandle {
+        let period = period.max(Timers::MIN_INTERVAL);
+        let now = self.context.now();
+        self.context
+            .timers_mut()
+            .schedule(self.current_node, now, period, Some(period))
+    }
+
+    /// Prevents this event from reaching any other component.
+    ///
+    /// Remaining listeners on this component still run. When called during the capture phase,
+    /// the bubbling phase is skipped entirely.
+    pub fn stop_propagation(&mut self) {
+        self.context.propagation_stopped = true;
+    }
+
+    /// Spawns a future on the async runtime.
+    ///
+    /// When the future completes, its output is delivered to this component as a
+    /// [`TaskOutput`](crate::events::TaskOutput) event. The future is cancelled if this component
+    /// is removed from the tree.
+    ///
+    /// Futures run on the tokio runtime the application was launched from if it is
+    /// multi-threaded, in which case the runtime loop occupies one of its worker threads.
+    /// Otherwise, including under a current-thread runtime, a runtime is started for them.
+    #[cfg(feature = "tokio")]
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let tx = self.context.handle().clone();
+        self.context
+            .executor_mut()
+            .spawn(self.current_node, tx, future);
+    }
+
+    /// Requests the interface to be redrawn after this update.
+    ///
+    /// Writing to a [`State`](crate::prelude::State) already requests a redraw. This is only
+    /// needed when a render function depends on data outside of component state.
+    pub fn request_redraw(&mut self) {
+        self.context.enqueue(Command::Redraw);
+    }
+
     /// Signals the runtime loop to shutdown.
     ///
     /// The runtime loop may defer or delay shutdown requests with discretion.
@@ -189,11 +363,65 @@ impl<E: Event> EventContext<'_, E> {
         self.context.enqueue(Command::Shutdown);
     }
 
+    /// Signals the runtime loop to shutdown, returning a value to the caller.
+    ///
+    /// The value is returned by [`LaunchBuilder::run`](crate::LaunchBuilder::run) after the
+    /// terminal is restored. If several values are given, the first one is kept.
+    pub fn exit_with<T: 'static>(&mut self, value: T) {
+        self.context.enqueue(Command::Exit(Box::new(value)));
+    }
+
+    /// Hands the terminal over to a closure, such as one running an external editor.
+    ///
+    /// After this update, the terminal is restored to its original state and input stops being
+    /// read while the closure runs. The terminal is then set up again and the interface is
+    /// redrawn in full. Events sent through a handle are delivered once the closure returns.
+    pub fn suspend(&mut self, f: impl FnOnce() + 'static) {
+        self.context.enqueue(Command::Suspend(Box::new(f)));
+    }
+
+    /// Stops the process as if the user pressed Ctrl+Z, resuming once the shell continues it.
+    ///
+    /// Raw mode keeps the terminal from stopping the process on Ctrl+Z, so applications can call
+    /// this from a key listener instead. The terminal is restored while stopped, as with
+    /// [`EventContext::suspend`].
+    #[cfg(unix)]
+    pub fn suspend_process(&mut self) {
+        self.suspend(|| {
+            // SAFETY: raise has no preconditions
+            unsafe { libc::raise(libc::SIGTSTP) };
+        });
+    }
+
+    /// Returns a handle for sending events to the application from other threads.
+    ///
+    /// Sending from within a listener blocks forever if the application has too many pending
+    /// events, since they are only handled once the listener returns. Move the handle to another
+    /// thread or task to send from there instead.
+    pub fn handle(&self) -> AppHandle {
+        AppHandle::from(self.context.handle().clone())
+    }
+
+    /// Returns the optional terminal features which were granted.
+    ///
+    /// Components can use this to offer alternatives when, for example, mouse input is disabled.
+    /// See [`Capabilities`](Capabilities#detection) for how features are detected.
+    pub fn capabilities(&self) -> Capabilities {
+        self.context.capabilities()
+    }
+
     /// Determines if this component is focused.
     pub fn is_focused(&self) -> bool {
         self.context.focused == Some(self.current_node)
     }
 
+    /// Determines if this component is the target of the event.
+    ///
+    /// Events without a target, such as [`Tick`](crate::events::Tick), never have a target.
+    pub fn is_target(&self) -> bool {
+        self.context.target() == Some(self.current_node)
+    }
+
     /// Returns the relative rectangular region of this component.
     pub fn area(&self) -> LogicalRect {
         LogicalRect::origin(self.rect.width, self.rect.height)
@@ -210,7 +438,7 @@ impl<E: MouseEvent> EventContext<'_, E> {
     ///
     /// A mouse hit is assigned to only one upper-most component containing the cursor.
     pub fn is_mouse_hit(&self) -> bool {
-        self.context.target == Some(self.current_node)
+        self.is_target()
     }
 
     /// Returns the relative mouse coordinates where this mouse event took place.
@@ -220,4 +448,15 @@ impl<E: MouseEvent> EventContext<'_, E> {
         let y = abs_y as i32 - self.rect.y;
         Some((x, y))
     }
+
+    /// Returns the mouse coordinates relative to the target of this mouse event.
+    ///
+    /// This is useful for ancestors handling an event on behalf of the target.
+    pub fn target_coords(&self) -> Option<(i32, i32)> {
+        let (_, rect) = self.context.target?;
+        let (abs_x, abs_y) = self.event.coords();
+        let x = abs_x as i32 - rect.x;
+        let y = abs_y as i32 - rect.y;
+        Some((x, y))
+    }
 }
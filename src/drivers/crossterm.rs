@@ -1,78 +1,130 @@
-use std::io::Write;
+use std::io::{self, Write};
 
 use crossterm::{
+    cursor::Show,
     event::{
         DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
-        EnableFocusChange, EnableMouseCapture, MouseEventKind,
+        EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags, MouseEventKind,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
-    Terminal,
+    Terminal, TerminalOptions, Viewport,
     prelude::{Backend, CrosstermBackend},
 };
 
 use crate::{
-    drivers::Driver,
+    drivers::{Capabilities, Driver, InputGate, Restorer, SharedWriter},
     input::{
-        Input, KeyCode, MediaKeyCode, ModifierKeyCode, ModifierKeyDirection, MouseButton,
-        MouseScrollDirection,
+        Input, KeyCode, KeyModifiers, MediaKeyCode, ModifierKeyCode, ModifierKeyDirection,
+        MouseButton, MouseScrollDirection,
     },
     transport::MessageSender,
 };
 
-pub struct CrosstermDriver<W: Write> {
-    terminal: Terminal<CrosstermBackend<W>>,
+pub struct CrosstermDriver {
+    terminal: Terminal<CrosstermBackend<SharedWriter>>,
+    writer: SharedWriter,
+    inline: bool,
+    requested: Capabilities,
+    granted: Capabilities,
+    gate: InputGate,
 }
 
-impl<W: Write> Driver for CrosstermDriver<W> {
-    type Backend = CrosstermBackend<W>;
+impl Driver for CrosstermDriver {
+    type Backend = CrosstermBackend<SharedWriter>;
 
     fn setup(&mut self) -> Result<(), <Self::Backend as Backend>::Error> {
         crossterm::terminal::enable_raw_mode()?;
-        crossterm::execute!(
-            self.terminal.backend_mut(),
-            EnterAlternateScreen,
-            EnableBracketedPaste,
-            EnableFocusChange,
-            EnableMouseCapture,
-        )?;
+
+        if !self.inline {
+            crossterm::execute!(self.terminal.backend_mut(), EnterAlternateScreen)?;
+        }
+
+        let mut granted = self.requested;
+
+        // crossterm writes its probe to standard output, which must stay clean when drawing to
+        // another writer, so keyboard enhancements are only granted when drawing to it
+        if granted.contains(Capabilities::KEYBOARD_ENHANCEMENT)
+            && !(self.writer.is_stdout()
+                && crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false))
+        {
+            granted.remove(Capabilities::KEYBOARD_ENHANCEMENT);
+        }
+
+        enable(self.terminal.backend_mut(), granted)?;
+        self.granted = granted;
+        self.gate.open();
         Ok(())
     }
 
-    fn teardown(mut self) -> Result<(), <Self::Backend as Backend>::Error> {
-        crossterm::terminal::disable_raw_mode()?;
-        crossterm::execute!(
+    fn teardown(&mut self) -> Result<(), <Self::Backend as Backend>::Error> {
+        self.gate.close();
+
+        // Keyboard enhancement flags are kept per screen, so they are popped before leaving
+        disable(
             self.terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableBracketedPaste,
-            DisableFocusChange,
-            DisableMouseCapture,
+            core::mem::take(&mut self.granted),
         )?;
-        Ok(())
+        crossterm::terminal::disable_raw_mode()?;
+
+        if self.inline {
+            // Leave the last frame in place and continue below it
+            let area = self.terminal.get_frame().area();
+            self.terminal
+                .set_cursor_position((0, area.bottom().saturating_sub(1)))?;
+            write!(self.terminal.backend_mut(), "\r\n")?;
+        } else {
+            crossterm::execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
+        }
+
+        self.terminal.show_cursor()
     }
 
     fn terminal(&mut self) -> &mut ratatui::Terminal<Self::Backend> {
         &mut self.terminal
     }
-}
 
-impl<W: Write> CrosstermDriver<W> {
-    pub fn new(writer: W) -> Result<Self, <<Self as Driver>::Backend as Backend>::Error> {
-        let backend = CrosstermBackend::new(writer);
-        let terminal = Terminal::new(backend)?;
-        Ok(Self { terminal })
+    fn restorer(&self) -> Restorer {
+        let writer = self.writer.clone();
+        let inline = self.inline;
+        let requested = self.requested;
+
+        Box::new(move || {
+            writer.try_with(|mut writer| {
+                let _ = disable(&mut writer, requested);
+
+                if !inline {
+                    let _ = crossterm::execute!(&mut writer, LeaveAlternateScreen);
+                }
+
+                let _ = crossterm::execute!(&mut writer, Show);
+            });
+
+            let _ = crossterm::terminal::disable_raw_mode();
+        })
     }
-}
 
-impl<W: Write> CrosstermDriver<W> {
-    pub fn spawn_event_handler(&self, tx: MessageSender) {
+    fn capabilities(&self) -> Capabilities {
+        self.granted
+    }
+
+    fn spawn_event_handler(&self, tx: MessageSender) {
+        let gate = self.gate.clone();
+
         std::thread::spawn(move || {
             loop {
-                let event = crossterm::event::read().expect("crossterm::event::read failed");
+                let event = gate
+                    .read(|| {
+                        crossterm::event::poll(InputGate::READ_TIMEOUT)?
+                            .then(crossterm::event::read)
+                            .transpose()
+                    })
+                    .expect("crossterm::event::read failed");
 
-                let msg = match normalize_input(event) {
-                    Some(input) => input.to_message(),
+                let msg = match event.and_then(normalize_input) {
+                    Some(input) => input.into_message(),
                     None => continue,
                 };
 
@@ -84,12 +136,84 @@ impl<W: Write> CrosstermDriver<W> {
     }
 }
 
+impl CrosstermDriver {
+    /// Creates a driver which draws to the given viewport.
+    ///
+    /// Non-fullscreen viewports are drawn in the main screen rather than the alternate screen,
+    /// leaving the last frame in the scrollback on teardown. The requested capabilities are
+    /// enabled during setup, as described in [`Capabilities`](Capabilities#detection).
+    pub fn new(
+        writer: SharedWriter,
+        viewport: Viewport,
+        requested: Capabilities,
+    ) -> Result<Self, <<Self as Driver>::Backend as Backend>::Error> {
+        let inline = viewport != Viewport::Fullscreen;
+        let backend = CrosstermBackend::new(writer.clone());
+        let terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
+
+        Ok(Self {
+            terminal,
+            writer,
+            inline,
+            requested,
+            granted: Capabilities::empty(),
+            gate: InputGate::default(),
+        })
+    }
+}
+
+/// Enables terminal capabilities.
+fn enable(writer: &mut impl Write, capabilities: Capabilities) -> io::Result<()> {
+    if capabilities.contains(Capabilities::MOUSE) {
+        crossterm::queue!(writer, EnableMouseCapture)?;
+    }
+
+    if capabilities.contains(Capabilities::PASTE) {
+        crossterm::queue!(writer, EnableBracketedPaste)?;
+    }
+
+    if capabilities.contains(Capabilities::FOCUS_CHANGE) {
+        crossterm::queue!(writer, EnableFocusChange)?;
+    }
+
+    if capabilities.contains(Capabilities::KEYBOARD_ENHANCEMENT) {
+        let flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+            | KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+        crossterm::queue!(writer, PushKeyboardEnhancementFlags(flags))?;
+    }
+
+    writer.flush()
+}
+
+/// Disables terminal capabilities.
+fn disable(writer: &mut impl Write, capabilities: Capabilities) -> io::Result<()> {
+    if capabilities.contains(Capabilities::KEYBOARD_ENHANCEMENT) {
+        crossterm::queue!(writer, PopKeyboardEnhancementFlags)?;
+    }
+
+    if capabilities.contains(Capabilities::FOCUS_CHANGE) {
+        crossterm::queue!(writer, DisableFocusChange)?;
+    }
+
+    if capabilities.contains(Capabilities::PASTE) {
+        crossterm::queue!(writer, DisableBracketedPaste)?;
+    }
+
+    if capabilities.contains(Capabilities::MOUSE) {
+        crossterm::queue!(writer, DisableMouseCapture)?;
+    }
+
+    writer.flush()
+}
+
 fn normalize_input(event: crossterm::event::Event) -> Option<Input> {
     match event {
         crossterm::event::Event::Mouse(mouse_event) => normalize_mouse_event(mouse_event),
         crossterm::event::Event::Key(key_event) => normalize_key_event(key_event),
+        crossterm::event::Event::Paste(text) => Some(Input::Paste { text }),
+        crossterm::event::Event::FocusGained => Some(Input::FocusGained),
+        crossterm::event::Event::FocusLost => Some(Input::FocusLost),
         crossterm::event::Event::Resize(width, height) => Some(Input::Resize { width, height }),
-        _ => None,
     }
 }
 
@@ -104,40 +228,60 @@ fn normalize_mouse_event(mouse_event: crossterm::event::MouseEvent) -> Option<In
 
     let x = mouse_event.column;
     let y = mouse_event.row;
+    let modifiers = normalize_modifiers(mouse_event.modifiers);
 
     match mouse_event.kind {
         MouseEventKind::Down(button) => {
             let button = normalize_button(button);
-            Some(Input::MouseDown { x, y, button })
+            Some(Input::MouseDown {
+                x,
+                y,
+                button,
+                modifiers,
+            })
         }
         MouseEventKind::Up(button) => {
             let button = normalize_button(button);
-            Some(Input::MouseUp { x, y, button })
+            Some(Input::MouseUp {
+                x,
+                y,
+                button,
+                modifiers,
+            })
         }
-        MouseEventKind::Moved => Some(Input::MouseHover { x, y }),
+        MouseEventKind::Moved => Some(Input::MouseHover { x, y, modifiers }),
         MouseEventKind::Drag(button) => {
             let button = normalize_button(button);
-            Some(Input::MouseDrag { x, y, button })
+            Some(Input::MouseDrag {
+                x,
+                y,
+                button,
+                modifiers,
+            })
         }
         MouseEventKind::ScrollUp => Some(Input::MouseScroll {
             x,
             y,
             direction: MouseScrollDirection::Up,
+            modifiers,
         }),
         MouseEventKind::ScrollDown => Some(Input::MouseScroll {
             x,
             y,
             direction: MouseScrollDirection::Down,
+            modifiers,
         }),
         MouseEventKind::ScrollLeft => Some(Input::MouseScroll {
             x,
             y,
             direction: MouseScrollDirection::Left,
+            modifiers,
         }),
         MouseEventKind::ScrollRight => Some(Input::MouseScroll {
             x,
             y,
             direction: MouseScrollDirection::Right,
+            modifiers,
         }),
     }
 }
@@ -182,14 +326,31 @@ fn normalize_key_event(key_event: crossterm::event::KeyEvent) -> Option<Input> {
     }
 
     let key = normalize_keycode(key_event.code)?;
+    let modifiers = normalize_modifiers(key_event.modifiers);
 
     match key_event.kind {
-        crossterm::event::KeyEventKind::Press => Some(Input::KeyPress { key }),
-        crossterm::event::KeyEventKind::Repeat => Some(Input::KeyRepeat { key }),
-        crossterm::event::KeyEventKind::Release => Some(Input::KeyRelease { key }),
+        crossterm::event::KeyEventKind::Press => Some(Input::KeyPress { key, modifiers }),
+        crossterm::event::KeyEventKind::Repeat => Some(Input::KeyRepeat { key, modifiers }),
+        crossterm::event::KeyEventKind::Release => Some(Input::KeyRelease { key, modifiers }),
     }
 }
 
+fn normalize_modifiers(modifiers: crossterm::event::KeyModifiers) -> KeyModifiers {
+    const MAPPING: [(crossterm::event::KeyModifiers, KeyModifiers); 6] = [
+        (crossterm::event::KeyModifiers::SHIFT, KeyModifiers::SHIFT),
+        (crossterm::event::KeyModifiers::CONTROL, KeyModifiers::CTRL),
+        (crossterm::event::KeyModifiers::ALT, KeyModifiers::ALT),
+        (crossterm::event::KeyModifiers::SUPER, KeyModifiers::SUPER),
+        (crossterm::event::KeyModifiers::HYPER, KeyModifiers::HYPER),
+        (crossterm::event::KeyModifiers::META, KeyModifiers::META),
+    ];
+
+    MAPPING
+        .into_iter()
+        .filter(|(from, _)| modifiers.contains(*from))
+        .fold(KeyModifiers::empty(), |acc, (_, to)| acc | to)
+}
+
 fn normalize_media_key(media_key_code: crossterm::event::MediaKeyCode) -> MediaKeyCode {
     match media_key_code {
         crossterm::event::MediaKeyCode::Play => MediaKeyCode::Play,
@@ -0,0 +1,64 @@
+use alloc::sync::Arc;
+use core::time::Duration;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+/// Stops terminal input from being read while the terminal is used by another program.
+///
+/// Reads are expected to time out, so that closing the gate does not wait for the next input.
+#[derive(Clone, Default)]
+pub struct InputGate(Arc<(Mutex<GateState>, Condvar)>);
+
+#[derive(Default)]
+struct GateState {
+    closed: bool,
+    reading: bool,
+}
+
+impl InputGate {
+    /// How long a read should wait for input, which bounds how long closing the gate takes.
+    pub const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+    /// Stops input from being read, waiting for a read in progress to finish.
+    pub fn close(&self) {
+        let state = self.lock();
+        let mut state = self.wait_while(state, |state| state.reading);
+        state.closed = true;
+    }
+
+    /// Allows input to be read again.
+    pub fn open(&self) {
+        self.lock().closed = false;
+        self.0.1.notify_all();
+    }
+
+    /// Runs a read once the gate is open.
+    ///
+    /// The read must not panic, or closing the gate would wait forever.
+    pub fn read<T>(&self, f: impl FnOnce() -> T) -> T {
+        let state = self.lock();
+        let mut state = self.wait_while(state, |state| state.closed);
+        state.reading = true;
+        drop(state);
+
+        let result = f();
+
+        self.lock().reading = false;
+        self.0.1.notify_all();
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GateState> {
+        self.0.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait_while<'a>(
+        &self,
+        state: MutexGuard<'a, GateState>,
+        condition: impl FnMut(&mut GateState) -> bool,
+    ) -> MutexGuard<'a, GateState> {
+        self.0
+            .1
+            .wait_while(state, condition)
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
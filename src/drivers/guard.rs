@@ -0,0 +1,59 @@
+use core::ops::{Deref, DerefMut};
+use std::thread;
+
+use ratatui::prelude::Backend;
+
+use crate::drivers::Driver;
+
+/// Tears down a driver when dropped, such as when returning early from an error.
+///
+/// If the current thread is panicking, teardown is left to the
+/// [`PanicHook`](crate::panic::PanicHook).
+pub struct DriverGuard<D: Driver> {
+    driver: D,
+    active: bool,
+}
+
+impl<D: Driver> Drop for DriverGuard<D> {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            let _ = self.teardown();
+        }
+    }
+}
+
+impl<D: Driver> Deref for DriverGuard<D> {
+    type Target = D;
+
+    fn deref(&self) -> &Self::Target {
+        &self.driver
+    }
+}
+
+impl<D: Driver> DerefMut for DriverGuard<D> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.driver
+    }
+}
+
+impl<D: Driver> DriverGuard<D> {
+    pub fn new(driver: D) -> Self {
+        Self {
+            driver,
+            active: false,
+        }
+    }
+
+    pub fn setup(&mut self) -> Result<(), <D::Backend as Backend>::Error> {
+        self.active = true;
+        self.driver.setup()
+    }
+
+    pub fn teardown(&mut self) -> Result<(), <D::Backend as Backend>::Error> {
+        if !core::mem::take(&mut self.active) {
+            return Ok(());
+        }
+
+        self.driver.teardown()
+    }
+}
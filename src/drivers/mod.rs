@@ -1,13 +1,148 @@
+use alloc::sync::Arc;
+use std::{
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    sync::{Mutex, MutexGuard, PoisonError, TryLockError},
+};
+
 use ratatui::{Terminal, prelude::Backend};
 
+use crate::transport::MessageSender;
+
+#[cfg(feature = "crossterm")]
 pub use crate::drivers::crossterm::CrosstermDriver;
+#[cfg(feature = "termwiz")]
+pub use crate::drivers::termwiz::TermwizDriver;
+pub use crate::drivers::{gate::InputGate, guard::DriverGuard, test::TestDriver};
 
+#[cfg(feature = "crossterm")]
 mod crossterm;
+mod gate;
+mod guard;
+#[cfg(feature = "termwiz")]
+mod termwiz;
+mod test;
+
+/// A function which restores the terminal to its original state from any thread.
+pub type Restorer = Box<dyn Fn() + Send + Sync + 'static>;
+
+/// A writer shared between a terminal and its [`Restorer`].
+#[derive(Clone)]
+pub struct SharedWriter {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    stdout: bool,
+}
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock().flush()
+    }
+}
+
+#[cfg_attr(not(feature = "crossterm"), allow(dead_code))]
+impl SharedWriter {
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+            stdout: false,
+        }
+    }
+
+    pub fn stdout() -> Self {
+        Self {
+            stdout: true,
+            ..Self::new(io::stdout())
+        }
+    }
+
+    /// Determines if the writer is standard output.
+    pub fn is_stdout(&self) -> bool {
+        self.stdout
+    }
+
+    /// Opens the controlling terminal, which is available even if standard output is redirected.
+    pub fn tty() -> io::Result<Self> {
+        #[cfg(unix)]
+        const PATH: &str = "/dev/tty";
+        #[cfg(windows)]
+        const PATH: &str = "CONOUT$";
+
+        let file = OpenOptions::new().read(true).write(true).open(PATH)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+
+    /// Runs a closure with the writer unless it is in use, such as by a panicking thread.
+    pub fn try_with(&self, f: impl FnOnce(&mut dyn Write)) {
+        let mut writer = match self.writer.try_lock() {
+            Ok(writer) => writer,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => return,
+        };
+
+        f(&mut *writer);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Box<dyn Write + Send>> {
+        self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+bitflags::bitflags! {
+    /// A set of optional terminal features.
+    ///
+    /// Requested with [`LaunchBuilder::capabilities`](crate::LaunchBuilder::capabilities), and
+    /// reported as granted by [`EventContext::capabilities`](crate::EventContext::capabilities).
+    ///
+    /// # Detection
+    ///
+    /// A granted feature is not necessarily supported by the terminal, as each driver decides
+    /// differently:
+    ///
+    /// - crossterm grants every requested feature, except [`Capabilities::KEYBOARD_ENHANCEMENT`]
+    ///   which is only granted if the terminal reports support for it while drawing to standard
+    ///   output.
+    /// - termwiz grants [`Capabilities::MOUSE`] and [`Capabilities::PASTE`] if terminfo lists
+    ///   them, and never grants the other features.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Capabilities: u8 {
+        /// Reports mouse events.
+        const MOUSE = 1 << 0;
+        /// Reports pasted text as a single [`Paste`](crate::events::Paste) event.
+        const PASTE = 1 << 1;
+        /// Reports when the terminal gains or loses focus.
+        const FOCUS_CHANGE = 1 << 2;
+        /// Reports key repeats and releases using the Kitty keyboard protocol.
+        const KEYBOARD_ENHANCEMENT = 1 << 3;
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
 
 pub trait Driver {
     type Backend: Backend;
 
+    /// Prepares the terminal for drawing and allows input to be read.
     fn setup(&mut self) -> Result<(), <Self::Backend as Backend>::Error>;
-    fn teardown(self) -> Result<(), <Self::Backend as Backend>::Error>;
+
+    /// Restores the terminal and stops input from being read until the next setup.
+    fn teardown(&mut self) -> Result<(), <Self::Backend as Backend>::Error>;
+
     fn terminal(&mut self) -> &mut Terminal<Self::Backend>;
+    fn restorer(&self) -> Restorer;
+
+    /// Returns the capabilities granted by the terminal during setup.
+    fn capabilities(&self) -> Capabilities;
+
+    /// Starts reading terminal input in the background, sending it to the runtime as messages.
+    ///
+    /// Reading pauses while the driver is torn down, and stops once the runtime is gone.
+    fn spawn_event_handler(&self, tx: MessageSender);
 }
@@ -0,0 +1,380 @@
+use alloc::sync::Arc;
+use core::cell::Cell;
+use std::{
+    io::{self, Write},
+    sync::{Mutex, PoisonError},
+};
+
+use ratatui::{
+    Terminal,
+    prelude::{Backend, TermwizBackend},
+};
+use termwiz::{
+    caps::ProbeHints,
+    escape::csi::{CSI, DecPrivateMode, DecPrivateModeCode, Mode},
+    input::{InputEvent, MouseButtons},
+    surface::{Change, CursorVisibility},
+    terminal::{SystemTerminal, Terminal as _, buffered::BufferedTerminal},
+};
+
+use crate::{
+    drivers::{Capabilities, Driver, InputGate, Restorer, SharedWriter},
+    input::{
+        Input, KeyCode, KeyModifiers, MediaKeyCode, ModifierKeyCode, ModifierKeyDirection,
+        MouseButton, MouseScrollDirection,
+    },
+    transport::MessageSender,
+};
+
+/// A driver which draws fullscreen to the controlling terminal using termwiz.
+///
+/// Terminal features are detected from terminfo and the environment rather than by querying the
+/// terminal.
+pub struct TermwizDriver {
+    terminal: Terminal<TermwizBackend>,
+    caps: termwiz::caps::Capabilities,
+    restorer: Arc<Mutex<Option<SystemTerminal>>>,
+    input: Cell<Option<SystemTerminal>>,
+    granted: Capabilities,
+    gate: InputGate,
+}
+
+impl Driver for TermwizDriver {
+    type Backend = TermwizBackend;
+
+    fn setup(&mut self) -> Result<(), <Self::Backend as Backend>::Error> {
+        let terminal = self
+            .terminal
+            .backend_mut()
+            .buffered_terminal_mut()
+            .terminal();
+
+        // Raw mode also enables the mouse and bracketed paste, if granted
+        terminal.set_raw_mode().map_err(io::Error::other)?;
+        terminal
+            .enter_alternate_screen()
+            .map_err(io::Error::other)?;
+
+        self.granted = granted(&self.caps);
+        self.gate.open();
+        Ok(())
+    }
+
+    fn teardown(&mut self) -> Result<(), <Self::Backend as Backend>::Error> {
+        self.gate.close();
+        disable(
+            &mut SharedWriter::tty()?,
+            core::mem::take(&mut self.granted),
+        )?;
+
+        let terminal = self
+            .terminal
+            .backend_mut()
+            .buffered_terminal_mut()
+            .terminal();
+
+        terminal.set_cooked_mode().map_err(io::Error::other)?;
+        terminal.exit_alternate_screen().map_err(io::Error::other)?;
+        terminal
+            .render(&[Change::CursorVisibility(CursorVisibility::Visible)])
+            .map_err(io::Error::other)?;
+        terminal.flush().map_err(io::Error::other)
+    }
+
+    fn terminal(&mut self) -> &mut Terminal<Self::Backend> {
+        // The backend only learns about a new size when asked to check for it
+        let _ = self
+            .terminal
+            .backend_mut()
+            .buffered_terminal_mut()
+            .check_for_resize();
+
+        &mut self.terminal
+    }
+
+    fn restorer(&self) -> Restorer {
+        let restorer = self.restorer.clone();
+
+        Box::new(move || {
+            let terminal = restorer
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take();
+
+            // Dropping a terminal resets its modes and the state it was created in, but only
+            // leaves the alternate screen if it entered it
+            if let Some(mut terminal) = terminal {
+                let _ = terminal.enter_alternate_screen();
+            }
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.granted
+    }
+
+    fn spawn_event_handler(&self, tx: MessageSender) {
+        let Some(mut terminal) = self.input.take() else {
+            return;
+        };
+        let gate = self.gate.clone();
+
+        std::thread::spawn(move || {
+            let mut buttons = MouseButtons::NONE;
+
+            loop {
+                let event = gate
+                    .read(|| terminal.poll_input(Some(InputGate::READ_TIMEOUT)))
+                    .expect("termwiz poll_input failed");
+
+                let msg = match event.and_then(|event| normalize_input(event, &mut buttons)) {
+                    Some(input) => input.into_message(),
+                    None => continue,
+                };
+
+                if tx.send(msg).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+impl TermwizDriver {
+    /// Creates a driver which draws fullscreen to the controlling terminal.
+    ///
+    /// The requested capabilities are enabled during setup if terminfo reports them as supported.
+    /// Focus changes and keyboard enhancements are not supported.
+    pub fn new(
+        requested: Capabilities,
+    ) -> Result<Self, <<Self as Driver>::Backend as Backend>::Error> {
+        // Features which were not requested are disabled, the rest are left to detection
+        let hints = ProbeHints::new_from_env()
+            .mouse_reporting((!requested.contains(Capabilities::MOUSE)).then_some(false))
+            .bracketed_paste((!requested.contains(Capabilities::PASTE)).then_some(false));
+        let caps = termwiz::caps::Capabilities::new_with_hints(hints).map_err(io::Error::other)?;
+
+        // Opened before raw mode is set, so that dropping them restores the original state
+        let restorer = SystemTerminal::new(caps.clone()).map_err(io::Error::other)?;
+        let input = SystemTerminal::new(caps.clone()).map_err(io::Error::other)?;
+        let terminal = SystemTerminal::new(caps.clone()).map_err(io::Error::other)?;
+        let buffered = BufferedTerminal::new(terminal).map_err(io::Error::other)?;
+        let terminal = Terminal::new(TermwizBackend::with_buffered_terminal(buffered))?;
+
+        Ok(Self {
+            terminal,
+            caps,
+            restorer: Arc::new(Mutex::new(Some(restorer))),
+            input: Cell::new(Some(input)),
+            granted: Capabilities::empty(),
+            gate: InputGate::default(),
+        })
+    }
+}
+
+/// Returns the capabilities enabled by raw mode.
+fn granted(caps: &termwiz::caps::Capabilities) -> Capabilities {
+    let mut granted = Capabilities::empty();
+    granted.set(Capabilities::MOUSE, caps.mouse_reporting());
+    granted.set(Capabilities::PASTE, caps.bracketed_paste());
+    granted
+}
+
+/// Disables terminal capabilities, which termwiz only does when the terminal is dropped.
+fn disable(writer: &mut impl Write, capabilities: Capabilities) -> io::Result<()> {
+    fn reset(writer: &mut impl Write, code: DecPrivateModeCode) -> io::Result<()> {
+        let mode = Mode::ResetDecPrivateMode(DecPrivateMode::Code(code));
+        write!(writer, "{}", CSI::Mode(mode))
+    }
+
+    if capabilities.contains(Capabilities::PASTE) {
+        reset(writer, DecPrivateModeCode::BracketedPaste)?;
+    }
+
+    if capabilities.contains(Capabilities::MOUSE) {
+        reset(writer, DecPrivateModeCode::SGRMouse)?;
+        reset(writer, DecPrivateModeCode::AnyEventMouse)?;
+    }
+
+    writer.flush()
+}
+
+fn normalize_input(event: InputEvent, buttons: &mut MouseButtons) -> Option<Input> {
+    match event {
+        InputEvent::Key(key_event) => normalize_key_event(key_event),
+        InputEvent::Mouse(mouse_event) => normalize_mouse_event(mouse_event, buttons),
+        InputEvent::Paste(text) => Some(Input::Paste { text }),
+        InputEvent::Resized { cols, rows } => Some(Input::Resize {
+            width: cols.try_into().unwrap_or(u16::MAX),
+            height: rows.try_into().unwrap_or(u16::MAX),
+        }),
+        InputEvent::PixelMouse(_) | InputEvent::Wake => None,
+    }
+}
+
+/// Converts a mouse event, given the buttons held during the previous one.
+///
+/// termwiz reports which buttons are held rather than what changed, so presses and releases are
+/// found by comparing against the previous event.
+fn normalize_mouse_event(
+    mouse_event: termwiz::input::MouseEvent,
+    buttons: &mut MouseButtons,
+) -> Option<Input> {
+    const BUTTONS: [(MouseButtons, MouseButton); 3] = [
+        (MouseButtons::LEFT, MouseButton::Left),
+        (MouseButtons::RIGHT, MouseButton::Right),
+        (MouseButtons::MIDDLE, MouseButton::Middle),
+    ];
+
+    // Positions are reported starting from one
+    let x = mouse_event.x.saturating_sub(1);
+    let y = mouse_event.y.saturating_sub(1);
+    let modifiers = normalize_modifiers(mouse_event.modifiers);
+    let held = mouse_event.mouse_buttons;
+
+    let positive = held.contains(MouseButtons::WHEEL_POSITIVE);
+    let direction = if held.contains(MouseButtons::VERT_WHEEL) {
+        Some(if positive {
+            MouseScrollDirection::Up
+        } else {
+            MouseScrollDirection::Down
+        })
+    } else if held.contains(MouseButtons::HORZ_WHEEL) {
+        Some(if positive {
+            MouseScrollDirection::Left
+        } else {
+            MouseScrollDirection::Right
+        })
+    } else {
+        None
+    };
+
+    if let Some(direction) = direction {
+        return Some(Input::MouseScroll {
+            x,
+            y,
+            direction,
+            modifiers,
+        });
+    }
+
+    let previous = core::mem::replace(buttons, held.clone());
+
+    for (from, button) in BUTTONS {
+        match (previous.contains(from.clone()), held.contains(from)) {
+            (false, true) => {
+                return Some(Input::MouseDown {
+                    x,
+                    y,
+                    button,
+                    modifiers,
+                });
+            }
+            (true, false) => {
+                return Some(Input::MouseUp {
+                    x,
+                    y,
+                    button,
+                    modifiers,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    match BUTTONS
+        .into_iter()
+        .find(|(from, _)| held.contains(from.clone()))
+    {
+        Some((_, button)) => Some(Input::MouseDrag {
+            x,
+            y,
+            button,
+            modifiers,
+        }),
+        None => Some(Input::MouseHover { x, y, modifiers }),
+    }
+}
+
+fn normalize_key_event(key_event: termwiz::input::KeyEvent) -> Option<Input> {
+    fn normalize_keycode(key_code: termwiz::input::KeyCode) -> Option<KeyCode> {
+        use termwiz::input::KeyCode as K;
+
+        match key_code {
+            K::Backspace => Some(KeyCode::Backspace),
+            K::Enter => Some(KeyCode::Enter),
+            K::LeftArrow | K::ApplicationLeftArrow => Some(KeyCode::Left),
+            K::RightArrow | K::ApplicationRightArrow => Some(KeyCode::Right),
+            K::UpArrow | K::ApplicationUpArrow => Some(KeyCode::Up),
+            K::DownArrow | K::ApplicationDownArrow => Some(KeyCode::Down),
+            K::Home | K::KeyPadHome => Some(KeyCode::Home),
+            K::End | K::KeyPadEnd => Some(KeyCode::End),
+            K::PageUp | K::KeyPadPageUp => Some(KeyCode::PageUp),
+            K::PageDown | K::KeyPadPageDown => Some(KeyCode::PageDown),
+            K::Tab => Some(KeyCode::Tab),
+            K::Delete => Some(KeyCode::Delete),
+            K::Insert => Some(KeyCode::Insert),
+            K::Function(n) => Some(KeyCode::F(n)),
+            K::Char(c) => Some(KeyCode::Char(c)),
+            K::Escape => Some(KeyCode::Esc),
+            K::CapsLock => Some(KeyCode::CapsLock),
+            K::ScrollLock => Some(KeyCode::ScrollLock),
+            K::NumLock => Some(KeyCode::NumLock),
+            K::PrintScreen => Some(KeyCode::PrintScreen),
+            K::Pause => Some(KeyCode::Pause),
+            K::Menu | K::Applications => Some(KeyCode::Menu),
+            K::KeyPadBegin => Some(KeyCode::KeypadBegin),
+            K::MediaPlayPause => Some(KeyCode::Media(MediaKeyCode::PlayPause)),
+            K::MediaStop => Some(KeyCode::Media(MediaKeyCode::Stop)),
+            K::MediaNextTrack => Some(KeyCode::Media(MediaKeyCode::TrackNext)),
+            K::MediaPrevTrack => Some(KeyCode::Media(MediaKeyCode::TrackPrevious)),
+            K::VolumeDown => Some(KeyCode::Media(MediaKeyCode::LowerVolume)),
+            K::VolumeUp => Some(KeyCode::Media(MediaKeyCode::RaiseVolume)),
+            K::VolumeMute => Some(KeyCode::Media(MediaKeyCode::MuteVolume)),
+            K::LeftShift => Some(modifier(ModifierKeyCode::Shift, ModifierKeyDirection::Left)),
+            K::RightShift => Some(modifier(
+                ModifierKeyCode::Shift,
+                ModifierKeyDirection::Right,
+            )),
+            K::LeftControl => Some(modifier(ModifierKeyCode::Ctrl, ModifierKeyDirection::Left)),
+            K::RightControl => Some(modifier(ModifierKeyCode::Ctrl, ModifierKeyDirection::Right)),
+            K::LeftAlt => Some(modifier(ModifierKeyCode::Alt, ModifierKeyDirection::Left)),
+            K::RightAlt => Some(modifier(ModifierKeyCode::Alt, ModifierKeyDirection::Right)),
+            K::LeftWindows => Some(modifier(ModifierKeyCode::Super, ModifierKeyDirection::Left)),
+            K::RightWindows => Some(modifier(
+                ModifierKeyCode::Super,
+                ModifierKeyDirection::Right,
+            )),
+            _ => None,
+        }
+    }
+
+    fn modifier(key: ModifierKeyCode, direction: ModifierKeyDirection) -> KeyCode {
+        KeyCode::Modifier(key, direction)
+    }
+
+    let modifiers = normalize_modifiers(key_event.modifiers);
+    let key = match normalize_keycode(key_event.key)? {
+        // Matches the key reported by other drivers for Shift+Tab
+        KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+        key => key,
+    };
+
+    Some(Input::KeyPress { key, modifiers })
+}
+
+fn normalize_modifiers(modifiers: termwiz::input::Modifiers) -> KeyModifiers {
+    use termwiz::input::Modifiers as M;
+
+    const MAPPING: [(M, KeyModifiers); 4] = [
+        (M::SHIFT, KeyModifiers::SHIFT),
+        (M::CTRL, KeyModifiers::CTRL),
+        (M::ALT, KeyModifiers::ALT),
+        (M::SUPER, KeyModifiers::SUPER),
+    ];
+
+    MAPPING
+        .into_iter()
+        .filter(|(from, _)| modifiers.contains(*from))
+        .fold(KeyModifiers::empty(), |acc, (_, to)| acc | to)
+}
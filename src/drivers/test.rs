@@ -0,0 +1,55 @@
+use core::convert::Infallible;
+
+use ratatui::{Terminal, backend::TestBackend, prelude::Backend};
+
+use crate::{
+    drivers::{Capabilities, Driver, Restorer},
+    transport::MessageSender,
+};
+
+/// A headless driver which draws to an in-memory buffer.
+pub struct TestDriver {
+    terminal: Terminal<TestBackend>,
+}
+
+impl Driver for TestDriver {
+    type Backend = TestBackend;
+
+    fn setup(&mut self) -> Result<(), <Self::Backend as Backend>::Error> {
+        Ok(())
+    }
+
+    fn teardown(&mut self) -> Result<(), <Self::Backend as Backend>::Error> {
+        Ok(())
+    }
+
+    fn terminal(&mut self) -> &mut Terminal<Self::Backend> {
+        &mut self.terminal
+    }
+
+    fn restorer(&self) -> Restorer {
+        Box::new(|| {})
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::all()
+    }
+
+    fn spawn_event_handler(&self, _tx: MessageSender) {}
+}
+
+impl TestDriver {
+    pub fn new(width: u16, height: u16) -> Self {
+        let backend = TestBackend::new(width, height);
+        let terminal = Terminal::new(backend).unwrap_or_else(|err: Infallible| match err {});
+        Self { terminal }
+    }
+
+    pub fn backend(&self) -> &TestBackend {
+        self.terminal.backend()
+    }
+
+    pub fn backend_mut(&mut self) -> &mut TestBackend {
+        self.terminal.backend_mut()
+    }
+}
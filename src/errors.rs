@@ -1,14 +1,39 @@
+use core::convert::Infallible;
 use std::io;
 
 use thiserror::Error;
 
+/// A boxed error returned by a component.
+pub type BoxError = Box<dyn core::error::Error + Send + Sync + 'static>;
+
+/// An error which stops the runtime loop.
 #[derive(Debug, Error)]
 pub enum RuntimeError {
     #[error(transparent)]
     Io(#[from] io::Error),
+
+    /// A listener or render function returned an error.
+    ///
+    /// The path lists the position of the component among its siblings at each level of the tree,
+    /// such as `/0/2` for the third child of the first child of the root component.
+    #[error("component at {path} failed: {source}")]
+    Component { path: String, source: BoxError },
+
+    /// An option of [`LaunchBuilder`](crate::LaunchBuilder) is not supported by the selected
+    /// [`DriverKind`](crate::DriverKind).
+    #[error("{0} is not supported by the selected driver")]
+    Unsupported(&'static str),
 }
 
-#[derive(Debug)]
+impl From<Infallible> for RuntimeError {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
+/// An error returned when sending an event to an application which has exited.
+#[derive(Debug, Error)]
+#[error("the application has exited")]
 pub struct SendError;
 
 impl<T> From<flume::SendError<T>> for SendError {
@@ -1,6 +1,9 @@
 use crate::{
-    input::{KeyCode, MouseButton, MouseScrollDirection},
-    transport::{Event, MouseEvent},
+    arena::{Arena, NodeId},
+    context::Context,
+    input::{KeyCode, KeyModifiers, MouseButton, MouseScrollDirection},
+    timers::TimerId,
+    transport::{Event, MouseEvent, Propagation},
 };
 
 /// An event requesting an additional update cycle before the next draw.
@@ -8,36 +11,80 @@ pub struct Tick;
 
 impl Event for Tick {}
 
+/// A timer created by this component fired.
+///
+/// This event is only delivered to the component which created the timer.
+pub struct Timer {
+    pub id: TimerId,
+    target: NodeId,
+}
+
+impl Event for Timer {
+    fn target(&self, _arena: &Arena, _context: &Context) -> Option<NodeId> {
+        Some(self.target)
+    }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Direct
+    }
+}
+
+impl Timer {
+    pub(crate) fn new(target: NodeId, id: TimerId) -> Self {
+        Self { id, target }
+    }
+}
+
 /// A mouse button was pressed.
+///
+/// Bubbles from the component under the cursor to the root.
 pub struct MouseDown {
     pub x: u16,
     pub y: u16,
     pub button: MouseButton,
+    pub modifiers: KeyModifiers,
+    /// The number of consecutive clicks at this position, such as `2` for a double click.
+    pub clicks: u8,
 }
 
 impl MouseEvent for MouseDown {
     fn coords(&self) -> (u16, u16) {
         (self.x, self.y)
     }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Bubble
+    }
 }
 
 /// A mouse button was released.
+///
+/// Bubbles from the component under the cursor to the root.
 pub struct MouseUp {
     pub x: u16,
     pub y: u16,
     pub button: MouseButton,
+    pub modifiers: KeyModifiers,
 }
 
 impl MouseEvent for MouseUp {
     fn coords(&self) -> (u16, u16) {
         (self.x, self.y)
     }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Bubble
+    }
 }
 
 /// The mouse cursor moved without a button held.
+///
+/// Reaches the component under the cursor and its ancestors first, then every other component,
+/// so that components can react to the cursor leaving them.
 pub struct MouseHover {
     pub x: u16,
     pub y: u16,
+    pub modifiers: KeyModifiers,
 }
 
 impl MouseEvent for MouseHover {
@@ -47,10 +94,14 @@ impl MouseEvent for MouseHover {
 }
 
 /// The mouse cursor moved with a button held.
+///
+/// Reaches the component under the cursor and its ancestors first, then every other component,
+/// so that components can react to the cursor leaving them.
 pub struct MouseDrag {
     pub x: u16,
     pub y: u16,
     pub button: MouseButton,
+    pub modifiers: KeyModifiers,
 }
 
 impl MouseEvent for MouseDrag {
@@ -60,46 +111,122 @@ impl MouseEvent for MouseDrag {
 }
 
 /// The mouse wheel was scrolled.
+///
+/// Bubbles from the component under the cursor to the root.
 pub struct MouseScroll {
     pub x: u16,
     pub y: u16,
     pub direction: MouseScrollDirection,
+    pub modifiers: KeyModifiers,
 }
 
 impl MouseEvent for MouseScroll {
     fn coords(&self) -> (u16, u16) {
         (self.x, self.y)
     }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Bubble
+    }
 }
 
 /// A keyboard button was pressed.
+///
+/// Key events bubble from the focused component to the root. If no component is focused, only
+/// the root component receives them.
 pub struct KeyPress {
     pub key: KeyCode,
+    pub modifiers: KeyModifiers,
 }
 
-impl Event for KeyPress {}
+impl Event for KeyPress {
+    fn target(&self, arena: &Arena, context: &Context) -> Option<NodeId> {
+        focus_target(arena, context)
+    }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Bubble
+    }
+}
 
 /// A keyboard button was repeated.
 ///
+/// Dispatched in the same manner as [`KeyPress`].
+///
 /// # Compatibility
 ///
-/// This event is only emitted on terminal emulators which support the Kitty keyboard protocol.
+/// This event is only emitted on terminal emulators which support the Kitty keyboard protocol,
+/// when [`Capabilities::KEYBOARD_ENHANCEMENT`](crate::Capabilities::KEYBOARD_ENHANCEMENT) is
+/// granted.
 pub struct KeyRepeat {
     pub key: KeyCode,
+    pub modifiers: KeyModifiers,
 }
 
-impl Event for KeyRepeat {}
+impl Event for KeyRepeat {
+    fn target(&self, arena: &Arena, context: &Context) -> Option<NodeId> {
+        focus_target(arena, context)
+    }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Bubble
+    }
+}
 
 /// A keyboard button was released.
 ///
+/// Dispatched in the same manner as [`KeyPress`].
+///
 /// # Compatibility
 ///
-/// This event is only emitted on terminal emulators which support the Kitty keyboard protocol.
+/// This event is only emitted on terminal emulators which support the Kitty keyboard protocol,
+/// when [`Capabilities::KEYBOARD_ENHANCEMENT`](crate::Capabilities::KEYBOARD_ENHANCEMENT) is
+/// granted.
 pub struct KeyRelease {
     pub key: KeyCode,
+    pub modifiers: KeyModifiers,
 }
 
-impl Event for KeyRelease {}
+impl Event for KeyRelease {
+    fn target(&self, arena: &Arena, context: &Context) -> Option<NodeId> {
+        focus_target(arena, context)
+    }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Bubble
+    }
+}
+
+/// Text was pasted into the terminal.
+///
+/// # Compatibility
+///
+/// This event is only emitted on terminal emulators which support bracketed paste, when
+/// [`Capabilities::PASTE`](crate::Capabilities::PASTE) is granted. Otherwise, pasted text is
+/// received as individual key presses.
+pub struct Paste {
+    pub text: String,
+}
+
+impl Event for Paste {}
+
+/// The terminal emulator window gained focus.
+///
+/// # Compatibility
+///
+/// This event is only emitted on terminal emulators which support focus reporting.
+pub struct TerminalFocusGained;
+
+impl Event for TerminalFocusGained {}
+
+/// The terminal emulator window lost focus.
+///
+/// # Compatibility
+///
+/// This event is only emitted on terminal emulators which support focus reporting.
+pub struct TerminalFocusLost;
+
+impl Event for TerminalFocusLost {}
 
 /// The terminal emulator resized the buffer.
 pub struct Resize {
@@ -109,7 +236,42 @@ pub struct Resize {
 
 impl Event for Resize {}
 
+/// A future spawned with [`EventContext::spawn`](crate::EventContext::spawn) completed.
+///
+/// This event is only delivered to the component which spawned the future.
+#[cfg(feature = "tokio")]
+pub struct TaskOutput<T> {
+    pub output: T,
+    target: NodeId,
+}
+
+#[cfg(feature = "tokio")]
+impl<T: Send + 'static> Event for TaskOutput<T> {
+    fn target(&self, _arena: &Arena, _context: &Context) -> Option<NodeId> {
+        Some(self.target)
+    }
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Direct
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> TaskOutput<T> {
+    pub(crate) fn new(target: NodeId, output: T) -> Self {
+        Self { output, target }
+    }
+}
+
 /// The focused component changed.
 pub struct FocusChanged {}
 
 impl Event for FocusChanged {}
+
+/// Targets the focused component, or the root component if nothing is focused.
+fn focus_target(arena: &Arena, context: &Context) -> Option<NodeId> {
+    context
+        .focused()
+        .filter(|&id| arena.get(id).is_some())
+        .or(Some(arena.root()))
+}
@@ -0,0 +1,80 @@
+use core::future::Future;
+
+use tokio::{
+    runtime::{Handle, Runtime, RuntimeFlavor},
+    task::AbortHandle,
+};
+
+use crate::{arena::NodeId, events::TaskOutput, transport::MessageSender};
+
+/// Runs futures spawned by components on a tokio runtime.
+///
+/// If the application is launched from within a multi-threaded tokio runtime, tasks are spawned
+/// onto it. Otherwise, a runtime is created when the first task is spawned. A current-thread
+/// runtime is never used, since the runtime loop blocks its only thread.
+#[derive(Default)]
+pub struct Executor {
+    runtime: Option<Runtime>,
+    tasks: Vec<(NodeId, AbortHandle)>,
+}
+
+impl Drop for Executor {
+    fn drop(&mut self) {
+        for (_, task) in self.tasks.drain(..) {
+            task.abort();
+        }
+
+        // Dropping a runtime blocks, which panics if launched from within another runtime
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl Executor {
+    /// Spawns a future whose output is sent to the given node as a [`TaskOutput`] event.
+    pub fn spawn<F>(&mut self, node: NodeId, tx: MessageSender, future: F)
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let task = self.handle().spawn(async move {
+            let output = future.await;
+            let _ = tx.send_async(TaskOutput::new(node, output)).await;
+        });
+
+        self.tasks.retain(|(_, task)| !task.is_finished());
+        self.tasks.push((node, task.abort_handle()));
+    }
+
+    /// Cancels the tasks of nodes which no longer exist.
+    pub fn retain(&mut self, mut exists: impl FnMut(NodeId) -> bool) {
+        self.tasks.retain(|(node, task)| {
+            if exists(*node) {
+                true
+            } else {
+                task.abort();
+                false
+            }
+        });
+    }
+
+    fn handle(&mut self) -> Handle {
+        if let Some(runtime) = &self.runtime {
+            return runtime.handle().clone();
+        }
+
+        if let Ok(handle) = Handle::try_current()
+            && handle.runtime_flavor() != RuntimeFlavor::CurrentThread
+        {
+            return handle;
+        }
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start tokio runtime");
+
+        self.runtime.insert(runtime).handle().clone()
+    }
+}
@@ -1,7 +1,9 @@
+use std::time::{Duration, Instant};
+
 use crate::{
     events::{
         KeyPress, KeyRelease, KeyRepeat, MouseDown, MouseDrag, MouseHover, MouseScroll, MouseUp,
-        Resize,
+        Paste, Resize, TerminalFocusGained, TerminalFocusLost,
     },
     transport::Message,
 };
@@ -19,6 +21,19 @@ pub enum ModifierKeyCode {
     IsoLevel5Shift,
 }
 
+bitflags::bitflags! {
+    /// A set of modifier keys held during an input.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct KeyModifiers: u8 {
+        const SHIFT = 1 << 0;
+        const CTRL = 1 << 1;
+        const ALT = 1 << 2;
+        const SUPER = 1 << 3;
+        const HYPER = 1 << 4;
+        const META = 1 << 5;
+    }
+}
+
 /// Physical position of a modifier key.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ModifierKeyDirection {
@@ -92,41 +107,58 @@ pub enum KeyCode {
     Modifier(ModifierKeyCode, ModifierKeyDirection),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// A raw input read from the terminal.
+///
+/// Drivers translate terminal input into this type, which the runtime turns into
+/// [`events`](crate::events).
+#[derive(Debug, Clone, PartialEq)]
 pub enum Input {
     MouseDown {
         x: u16,
         y: u16,
         button: MouseButton,
+        modifiers: KeyModifiers,
     },
     MouseUp {
         x: u16,
         y: u16,
         button: MouseButton,
+        modifiers: KeyModifiers,
     },
     MouseHover {
         x: u16,
         y: u16,
+        modifiers: KeyModifiers,
     },
     MouseDrag {
         x: u16,
         y: u16,
         button: MouseButton,
+        modifiers: KeyModifiers,
     },
     MouseScroll {
         x: u16,
         y: u16,
         direction: MouseScrollDirection,
+        modifiers: KeyModifiers,
     },
     KeyPress {
         key: KeyCode,
+        modifiers: KeyModifiers,
     },
     KeyRepeat {
         key: KeyCode,
+        modifiers: KeyModifiers,
     },
     KeyRelease {
         key: KeyCode,
+        modifiers: KeyModifiers,
     },
+    Paste {
+        text: String,
+    },
+    FocusGained,
+    FocusLost,
     Resize {
         width: u16,
         height: u16,
@@ -136,21 +168,95 @@ pub enum Input {
 impl From<Input> for Message {
     fn from(value: Input) -> Self {
         match value {
-            Input::MouseDown { x, y, button } => Message::new(MouseDown { x, y, button }),
-            Input::MouseUp { x, y, button } => Message::new(MouseUp { x, y, button }),
-            Input::MouseHover { x, y } => Message::new(MouseHover { x, y }),
-            Input::MouseDrag { x, y, button } => Message::new(MouseDrag { x, y, button }),
-            Input::MouseScroll { x, y, direction } => Message::new(MouseScroll { x, y, direction }),
-            Input::KeyPress { key } => Message::new(KeyPress { key }),
-            Input::KeyRepeat { key } => Message::new(KeyRepeat { key }),
-            Input::KeyRelease { key } => Message::new(KeyRelease { key }),
+            Input::MouseDown {
+                x,
+                y,
+                button,
+                modifiers,
+            } => Message::new(MouseDown {
+                x,
+                y,
+                button,
+                modifiers,
+                clicks: 1,
+            }),
+            Input::MouseUp {
+                x,
+                y,
+                button,
+                modifiers,
+            } => Message::new(MouseUp {
+                x,
+                y,
+                button,
+                modifiers,
+            }),
+            Input::MouseHover { x, y, modifiers } => Message::new(MouseHover { x, y, modifiers }),
+            Input::MouseDrag {
+                x,
+                y,
+                button,
+                modifiers,
+            } => Message::new(MouseDrag {
+                x,
+                y,
+                button,
+                modifiers,
+            }),
+            Input::MouseScroll {
+                x,
+                y,
+                direction,
+                modifiers,
+            } => Message::new(MouseScroll {
+                x,
+                y,
+                direction,
+                modifiers,
+            }),
+            Input::KeyPress { key, modifiers } => Message::new(KeyPress { key, modifiers }),
+            Input::KeyRepeat { key, modifiers } => Message::new(KeyRepeat { key, modifiers }),
+            Input::KeyRelease { key, modifiers } => Message::new(KeyRelease { key, modifiers }),
+            Input::Paste { text } => Message::new(Paste { text }),
+            Input::FocusGained => Message::new(TerminalFocusGained),
+            Input::FocusLost => Message::new(TerminalFocusLost),
             Input::Resize { width, height } => Message::new(Resize { width, height }),
         }
     }
 }
 
 impl Input {
-    pub fn to_message(self) -> Message {
+    pub fn into_message(self) -> Message {
         Message::from(self)
     }
 }
+
+/// Counts consecutive clicks of the same button at the same position.
+#[derive(Default)]
+pub struct ClickCounter {
+    last: Option<(Instant, u16, u16, MouseButton)>,
+    count: u8,
+}
+
+impl ClickCounter {
+    /// The maximum delay between two clicks of a multi-click.
+    const INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Registers a click and returns the number of consecutive clicks.
+    pub fn register(&mut self, now: Instant, x: u16, y: u16, button: MouseButton) -> u8 {
+        let repeated = self.last.is_some_and(|(at, last_x, last_y, last_button)| {
+            last_button == button
+                && (last_x, last_y) == (x, y)
+                && now.duration_since(at) <= Self::INTERVAL
+        });
+
+        self.count = if repeated {
+            self.count.saturating_add(1)
+        } else {
+            1
+        };
+        self.last = Some((now, x, y, button));
+
+        self.count
+    }
+}
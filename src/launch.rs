@@ -1,16 +1,74 @@
-use std::io;
+use core::time::Duration;
+use std::io::Write;
+#[cfg(feature = "crossterm")]
+use std::io::{self, IsTerminal};
+
+use ratatui::{Viewport, prelude::Backend};
+
+#[cfg(feature = "crossterm")]
+use crate::drivers::CrosstermDriver;
+#[cfg(feature = "termwiz")]
+use crate::drivers::TermwizDriver;
 
 use crate::{
     component::{Component, Factory},
-    drivers::{CrosstermDriver, Driver},
+    drivers::{Capabilities, Driver, DriverGuard, SharedWriter},
     errors::RuntimeError,
-    runtime::Runtime,
-    transport::MessageBus,
+    input::Input,
+    panic::PanicHook,
+    runtime::{Runtime, RuntimeConfig},
+    transport::{AppHandle, MessageBus},
 };
 
 /// Builder for configuring and launching an application.
 #[derive(Default)]
-pub struct LaunchBuilder {}
+pub struct LaunchBuilder {
+    bus: MessageBus,
+    config: RuntimeConfig,
+    viewport: Viewport,
+    capabilities: Capabilities,
+    output: Output,
+    driver: DriverKind,
+}
+
+/// The terminal library used to draw the interface and read input.
+///
+/// Each driver is enabled by the feature of the same name. Defaults to crossterm if its feature
+/// is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DriverKind {
+    /// Draws with crossterm, which supports every option of [`LaunchBuilder`].
+    #[cfg(feature = "crossterm")]
+    Crossterm,
+    /// Draws with termwiz, which detects terminal features using terminfo.
+    ///
+    /// The interface is always drawn fullscreen to the controlling terminal. Launching returns
+    /// [`RuntimeError::Unsupported`] if [`LaunchBuilder::inline`], [`LaunchBuilder::tty`] or
+    /// [`LaunchBuilder::writer`] was used. Focus changes and keyboard enhancements are not
+    /// supported.
+    #[cfg(feature = "termwiz")]
+    Termwiz,
+}
+
+impl Default for DriverKind {
+    fn default() -> Self {
+        #[cfg(feature = "crossterm")]
+        return Self::Crossterm;
+        #[cfg(not(feature = "crossterm"))]
+        return Self::Termwiz;
+    }
+}
+
+/// Where the interface is drawn.
+#[derive(Default)]
+#[cfg_attr(not(feature = "crossterm"), allow(dead_code))]
+enum Output {
+    #[default]
+    Stdout,
+    Tty,
+    Writer(SharedWriter),
+}
 
 impl LaunchBuilder {
     /// Creates a new builder with default settings.
@@ -18,32 +76,248 @@ impl LaunchBuilder {
         LaunchBuilder::default()
     }
 
+    /// Returns a handle for sending events to the application once launched.
+    ///
+    /// Events sent before launch are delivered once the runtime loop starts.
+    pub fn handle(&self) -> AppHandle {
+        AppHandle::from(self.bus.handle().clone())
+    }
+
+    /// Limits how many frames are drawn per second.
+    ///
+    /// Frames are only drawn when the interface changed, so this is an upper bound. Passing `0`
+    /// removes the limit, which is the default.
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.config.frame_interval = match fps {
+            0 => None,
+            fps => Some(Duration::from_secs(1) / fps),
+        };
+        self
+    }
+
+    /// Sets how long events are collected into a single update before drawing.
+    ///
+    /// A longer window reduces redundant draws during bursts of events, such as scrolling, at the
+    /// cost of latency. Defaults to 16 milliseconds.
+    pub fn event_batch_window(mut self, window: Duration) -> Self {
+        self.config.batch_window = Some(window);
+        self
+    }
+
+    /// Sets how many events can be pending before senders are blocked.
+    ///
+    /// Handles returned by [`LaunchBuilder::handle`] before this call are disconnected. Defaults
+    /// to 128.
+    pub fn bus_capacity(mut self, capacity: usize) -> Self {
+        self.bus = MessageBus::with_capacity(capacity);
+        self
+    }
+
+    /// Draws after every event instead of batching events.
+    ///
+    /// This minimizes latency at the cost of drawing more often. Any frame rate limit set with
+    /// [`LaunchBuilder::fps`] is removed.
+    pub fn low_latency(mut self) -> Self {
+        self.config.frame_interval = None;
+        self.config.batch_window = None;
+        self
+    }
+
+    /// Draws the application in a fixed number of lines below the cursor.
+    ///
+    /// Unlike the default fullscreen mode, the alternate screen is not used. When the application
+    /// exits, its last frame is left in the terminal scrollback.
+    ///
+    /// The crossterm driver queries the cursor position through standard output, even if the
+    /// interface is drawn elsewhere. Launching returns [`RuntimeError::Unsupported`] if the
+    /// interface is drawn elsewhere while standard output is not a terminal.
+    pub fn inline(mut self, height: u16) -> Self {
+        self.viewport = Viewport::Inline(height);
+        self
+    }
+
+    /// Draws the interface to the controlling terminal instead of standard output.
+    ///
+    /// This keeps standard output free for the result of the program, such as when it is piped
+    /// into another command. Input is always read from the terminal.
+    ///
+    /// [`Capabilities::KEYBOARD_ENHANCEMENT`] is not granted, since detecting it writes to
+    /// standard output.
+    pub fn tty(mut self) -> Self {
+        self.output = Output::Tty;
+        self
+    }
+
+    /// Draws the interface to the given writer instead of standard output.
+    ///
+    /// The writer is expected to be connected to the terminal input is read from. As with
+    /// [`LaunchBuilder::tty`], [`Capabilities::KEYBOARD_ENHANCEMENT`] is not granted.
+    pub fn writer(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.output = Output::Writer(SharedWriter::new(writer));
+        self
+    }
+
+    /// Sets which optional terminal features to enable.
+    ///
+    /// The granted set is available through
+    /// [`EventContext::capabilities`](crate::EventContext::capabilities), and depends on the
+    /// driver as described in [`Capabilities`](Capabilities#detection). All features are
+    /// requested by default. Disabling [`Capabilities::MOUSE`] lets users select text with the
+    /// mouse.
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Sets the terminal library used to draw the interface and read input.
+    pub fn driver(mut self, driver: DriverKind) -> Self {
+        self.driver = driver;
+        self
+    }
+
+    /// Sets a handler for errors returned by listeners and render functions.
+    ///
+    /// The application keeps running after an error is handled. Without a handler, the first
+    /// error stops the application and is returned from [`LaunchBuilder::launch`].
+    pub fn on_error(mut self, handler: impl FnMut(RuntimeError) + 'static) -> Self {
+        self.config.on_error = Some(Box::new(handler));
+        self
+    }
+
     /// Launches the application with the given root component.
+    ///
+    /// The terminal is restored when the application exits, returns an error or panics.
     pub fn launch(self, app: Factory) -> Result<(), RuntimeError> {
+        self.run::<()>(app).map(|_| ())
+    }
+
+    /// Launches the application and returns the value it exited with.
+    ///
+    /// The value is given by [`EventContext::exit_with`](crate::EventContext::exit_with) and
+    /// returned after the terminal is restored. Returns `None` if the application exited without
+    /// a value, or with a value of another type.
+    pub fn run<T: 'static>(self, app: Factory) -> Result<Option<T>, RuntimeError> {
+        match self.driver {
+            #[cfg(feature = "crossterm")]
+            DriverKind::Crossterm => {
+                // The cursor position query would be written into redirected standard output
+                if self.viewport != Viewport::Fullscreen
+                    && !matches!(self.output, Output::Stdout)
+                    && !io::stdout().is_terminal()
+                {
+                    return Err(RuntimeError::Unsupported(
+                        "an inline viewport with redirected standard output",
+                    ));
+                }
+
+                let writer = match &self.output {
+                    Output::Stdout => SharedWriter::stdout(),
+                    Output::Tty => SharedWriter::tty()?,
+                    Output::Writer(writer) => writer.clone(),
+                };
+                let driver =
+                    CrosstermDriver::new(writer, self.viewport.clone(), self.capabilities)?;
+                self.run_with(driver, app)
+            }
+            #[cfg(feature = "termwiz")]
+            DriverKind::Termwiz => {
+                if self.viewport != Viewport::Fullscreen {
+                    return Err(RuntimeError::Unsupported("an inline viewport"));
+                }
+
+                match self.output {
+                    Output::Stdout => {}
+                    Output::Tty => return Err(RuntimeError::Unsupported("drawing to the tty")),
+                    Output::Writer(_) => {
+                        return Err(RuntimeError::Unsupported("drawing to a writer"));
+                    }
+                }
+
+                let driver = TermwizDriver::new(self.capabilities)?;
+                self.run_with(driver, app)
+            }
+        }
+    }
+
+    fn run_with<D, T>(self, driver: D, app: Factory) -> Result<Option<T>, RuntimeError>
+    where
+        D: Driver,
+        T: 'static,
+        RuntimeError: From<<D::Backend as Backend>::Error>,
+    {
         let node = app(Component::new(), ());
 
-        let bus = MessageBus::new();
-        let handle = bus.handle();
-        let mut driver = CrosstermDriver::new(io::stdout())?;
+        let bus = self.bus;
+
+        let _hook = PanicHook::install(driver.restorer());
+        let mut driver = DriverGuard::new(driver);
 
         driver.setup()?;
-        driver.spawn_event_handler(handle.clone());
+        driver.spawn_event_handler(bus.handle().clone());
 
-        let mut runtime = Runtime::new(node, bus);
+        let mut runtime = Runtime::new(node, bus, self.config);
+        runtime.set_capabilities(driver.capabilities());
 
         loop {
-            runtime.draw(&mut driver)?;
-            runtime.update();
+            runtime.draw(&mut *driver)?;
+            runtime.update()?;
 
             if runtime.should_exit() {
                 break;
             }
+
+            let suspensions = runtime.take_suspensions();
+
+            if !suspensions.is_empty() {
+                suspend(&mut driver, &mut runtime, suspensions)?;
+            }
         }
 
         driver.teardown()?;
 
-        Ok(())
+        let value = runtime
+            .take_exit_value()
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value);
+
+        Ok(value)
+    }
+}
+
+/// Runs closures given to [`EventContext::suspend`](crate::EventContext::suspend) while the
+/// driver is torn down.
+fn suspend<D>(
+    driver: &mut DriverGuard<D>,
+    runtime: &mut Runtime,
+    suspensions: Vec<Box<dyn FnOnce()>>,
+) -> Result<(), RuntimeError>
+where
+    D: Driver,
+    RuntimeError: From<<D::Backend as Backend>::Error>,
+{
+    let size = driver.terminal().size()?;
+
+    driver.teardown()?;
+    suspensions.into_iter().for_each(|f| f());
+    driver.setup()?;
+
+    // Another program may have drawn over the interface, so it is cleared and drawn in full
+    let terminal = driver.terminal();
+    let resized = terminal.size()?;
+    terminal.resize(resized.into())?;
+
+    runtime.set_capabilities(driver.capabilities());
+    runtime.request_redraw();
+
+    if resized != size {
+        let input = Input::Resize {
+            width: resized.width,
+            height: resized.height,
+        };
+        runtime.post(input.into_message());
     }
+
+    Ok(())
 }
 
 /// Launches an app with the given root component.
@@ -37,36 +37,53 @@
 //!
 //! vtui is simple and predictable. Its core runtime loop has three phases:
 //!
-//! 1. **Draw** - vtui builds the layout and renders the interface
+//! 1. **Draw** - vtui builds the layout and renders the interface, if anything changed
 //! 2. **Update** - components handle events, bubbling up from children to parents
 //! 3. **Commit** - the runtime interprets actions enqueued during update
 //!
 //! The component tree is traversed in a deterministic manner that never surprises the developer.
 //!
-//! Furthermore, it is highly extensible through its broadcast-only event system. The developer
-//! never needs to worry about hidden filtering behavior, as filtering is the responsibility of the
-//! listening component. vtui offers batteries-included systems such as a focus system to help the
-//! developer route events to components.
+//! Every event declares which components receive it through its [`Propagation`]:
+//!
+//! - Custom events and terminal-wide events such as resizes and pastes are broadcast to every
+//!   component.
+//! - Keyboard events bubble from the focused component to the root, and clicks and scrolls bubble
+//!   from the component under the cursor to the root.
+//! - Mouse movement reaches the component under the cursor and its ancestors before the rest of
+//!   the tree.
+//! - Timers and task output are delivered only to the component which started them.
+//!
+//! Components may intercept events before their children with capturing listeners, and any
+//! listener can stop an event from propagating to the rest of the tree.
 
 extern crate alloc;
 
+#[cfg(not(any(feature = "crossterm", feature = "termwiz")))]
+compile_error!("at least one of the `crossterm` and `termwiz` features must be enabled");
+
 pub use crate::{
     canvas::Canvas,
     component::{Ui, UiNode},
     context::EventContext,
-    launch::{LaunchBuilder, launch},
+    drivers::Capabilities,
+    errors::{BoxError, RuntimeError, SendError},
+    input::Input,
+    launch::{DriverKind, LaunchBuilder, launch},
+    timers::{TimerHandle, TimerId},
+    transport::{AppHandle, Event, Propagation},
 };
 
 /// Event types emitted by the framework.
 pub mod events;
+pub mod testing;
 
 /// Common imports for building components.
 pub mod prelude {
     pub use crate::{
-        component::{Component, Factory, Node, Props},
+        component::{Component, ErrorInfo, Factory, Node, Props},
         input::{
-            KeyCode, MediaKeyCode, ModifierKeyCode, ModifierKeyDirection, MouseButton,
-            MouseScrollDirection,
+            KeyCode, KeyModifiers, MediaKeyCode, ModifierKeyCode, ModifierKeyDirection,
+            MouseButton, MouseScrollDirection,
         },
         layout::{Flow, Inset, LogicalRect, Measure, Placement},
         state::State,
@@ -76,15 +93,20 @@ pub mod prelude {
 
 pub(crate) mod arena;
 pub(crate) mod canvas;
+pub(crate) mod clock;
 pub(crate) mod component;
 pub(crate) mod context;
 pub(crate) mod drivers;
 pub(crate) mod errors;
+#[cfg(feature = "tokio")]
+pub(crate) mod executor;
 pub(crate) mod input;
 pub(crate) mod layout;
 pub(crate) mod listeners;
+pub(crate) mod panic;
 pub(crate) mod runtime;
 pub(crate) mod state;
+pub(crate) mod timers;
 pub(crate) mod transport;
 
 mod launch;
@@ -1,8 +1,8 @@
 use core::any::{Any, TypeId};
 
-use crate::{context::EventContext, transport::Event};
+use crate::{context::EventContext, errors::BoxError, transport::Event};
 
-type EventListener<E> = Box<dyn FnMut(&mut EventContext<E>)>;
+type EventListener<E> = Box<dyn FnMut(&mut EventContext<E>) -> Result<(), BoxError>>;
 
 #[derive(Default)]
 pub struct Listeners {
@@ -14,7 +14,7 @@ impl Listeners {
     pub fn push<E, F>(&mut self, callback: F)
     where
         E: Event,
-        F: FnMut(&mut EventContext<E>) + 'static,
+        F: FnMut(&mut EventContext<E>) -> Result<(), BoxError> + 'static,
     {
         let id = TypeId::of::<E>();
         let cb = Box::new(callback) as EventListener<E>;
@@ -28,9 +28,11 @@ impl Listeners {
         }
     }
 
-    pub fn dispatch<E: Event>(&mut self, event: &mut EventContext<E>) {
-        if let Some(listeners) = self.get_mut::<E>() {
-            listeners.iter_mut().for_each(|cb| cb(event));
+    /// Runs the listeners for an event, stopping at the first error.
+    pub fn dispatch<E: Event>(&mut self, event: &mut EventContext<E>) -> Result<(), BoxError> {
+        match self.get_mut::<E>() {
+            Some(listeners) => listeners.iter_mut().try_for_each(|cb| cb(event)),
+            None => Ok(()),
         }
     }
 
@@ -0,0 +1,81 @@
+use alloc::sync::Arc;
+use core::{any::Any, cell::Cell};
+use std::{
+    panic::{self, AssertUnwindSafe, PanicHookInfo},
+    thread,
+};
+
+use crate::drivers::Restorer;
+
+type Hook = Box<dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static>;
+
+thread_local! {
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Restores the terminal before the panic message is printed.
+///
+/// Only panics on the thread which installed the hook are handled, as other threads do not stop
+/// the runtime loop. The previous hook is reinstated when dropped.
+pub struct PanicHook {
+    previous: Arc<Hook>,
+}
+
+impl Drop for PanicHook {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            return;
+        }
+
+        drop(panic::take_hook());
+
+        let previous = self.previous.clone();
+        panic::set_hook(Box::new(move |info| previous(info)));
+    }
+}
+
+impl PanicHook {
+    pub fn install(restore: Restorer) -> Self {
+        let previous = Arc::new(panic::take_hook());
+        let thread = thread::current().id();
+
+        panic::set_hook({
+            let previous = previous.clone();
+            Box::new(move |info| {
+                if CATCHING.get() > 0 {
+                    return;
+                }
+
+                if thread::current().id() == thread {
+                    restore();
+                }
+
+                previous(info);
+            })
+        });
+
+        Self { previous }
+    }
+}
+
+/// Runs a closure, catching any panic and returning its message.
+///
+/// Panics caught this way are not reported by the [`PanicHook`], as the application continues.
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    CATCHING.set(CATCHING.get() + 1);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(CATCHING.get() - 1);
+
+    result.map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Extracts the message of a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("component panicked")
+    }
+}
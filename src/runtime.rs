@@ -1,25 +1,58 @@
+use core::any::Any;
 use std::time::{Duration, Instant};
 
 use ratatui::prelude::Backend;
 
 use crate::{
     arena::Arena,
+    clock::Clock,
     component::Node,
-    context::Context,
-    drivers::Driver,
+    context::{Command, Context},
+    drivers::{Capabilities, Driver},
     errors::RuntimeError,
-    events::Tick,
+    events::{MouseDown, Resize, Tick},
+    input::ClickCounter,
+    state,
     transport::{Dispatch, Message, MessageBus},
 };
 
+/// Handles errors returned by components.
+pub type ErrorHandler = Box<dyn FnMut(RuntimeError)>;
+
+/// Settings of the runtime loop.
+pub struct RuntimeConfig {
+    /// The minimum time between two draws, or `None` to draw as often as needed.
+    pub frame_interval: Option<Duration>,
+
+    /// How long to keep collecting events after the first one, or `None` to draw after every
+    /// event.
+    pub batch_window: Option<Duration>,
+
+    /// Handles errors returned by components, or `None` to stop the runtime loop instead.
+    pub on_error: Option<ErrorHandler>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            frame_interval: None,
+            batch_window: Some(Duration::from_millis(16)),
+            on_error: None,
+        }
+    }
+}
+
 pub struct Runtime {
     arena: Arena,
     context: Context,
     bus: MessageBus,
+    clicks: ClickCounter,
+    config: RuntimeConfig,
+    last_draw: Option<Instant>,
 }
 
 impl Runtime {
-    pub fn new(node: Node, bus: MessageBus) -> Self {
+    pub fn new(node: Node, bus: MessageBus, config: RuntimeConfig) -> Self {
         let arena = Arena::from(node);
         let handle = bus.handle();
         let context = Context::new(handle.clone());
@@ -28,6 +61,9 @@ impl Runtime {
             arena,
             context,
             bus,
+            clicks: ClickCounter::default(),
+            config,
+            last_draw: None,
         }
     }
 
@@ -36,44 +72,244 @@ impl Runtime {
         D: Driver,
         RuntimeError: From<<<D as Driver>::Backend as Backend>::Error>,
     {
+        if !self.context.redraw_requested() {
+            return Ok(());
+        }
+
+        if let Some(next_frame) = self.next_frame()
+            && self.context.now() < next_frame
+        {
+            return Ok(());
+        }
+
+        self.context.clear_redraw_request();
+        self.last_draw = Some(self.context.now());
+
         let terminal = driver.terminal();
 
-        terminal.draw(|f| {
-            self.arena.render(f, &self.context);
-        })?;
+        loop {
+            terminal.draw(|f| {
+                self.arena.render(f, &self.context);
+            })?;
 
-        Ok(())
+            // The failed frame stays on screen until the next draw, so the fallback is drawn now
+            if !self.arena.recover() {
+                break;
+            }
+
+            self.prune();
+        }
+
+        self.report()
     }
 
-    pub fn update(&mut self) {
-        if self.context.tick_requested() {
-            let _ = self.bus.handle().send(Tick {});
-            self.context.clear_tick_request();
+    pub fn update(&mut self) -> Result<(), RuntimeError> {
+        self.post_tick();
+
+        let started = Instant::now();
+
+        let msg = match (self.context.take_posted(), self.wake_deadline()) {
+            (Some(msg), _) => Some(msg),
+            (None, Some(wake)) => self.bus.recv_deadline(wake),
+            (None, None) => Some(self.bus.recv()),
+        };
+
+        if let Some(msg) = msg {
+            self.dispatch(msg)?;
         }
 
-        let deadline = Instant::now() + Duration::from_millis(16);
-        let msg = self.bus.recv();
+        self.fire_timers()?;
+
+        let Some(window) = self.config.batch_window else {
+            return Ok(());
+        };
+
+        let deadline = started + window;
+
+        loop {
+            if let Some(msg) = self.context.take_posted() {
+                self.dispatch(msg)?;
+                continue;
+            }
+
+            let wake = match self.context.timers().next_deadline() {
+                Some(timer) => timer.min(deadline),
+                None => deadline,
+            };
+
+            match self.bus.recv_deadline(wake) {
+                Some(msg) => self.dispatch(msg)?,
+                None if wake >= deadline => break,
+                None => {}
+            }
+
+            self.fire_timers()?;
+        }
+
+        Ok(())
+    }
 
-        self.dispatch(msg);
+    /// Handles due timers and all pending events without waiting.
+    pub fn update_pending(&mut self) -> Result<(), RuntimeError> {
+        self.post_tick();
+        self.fire_timers()?;
 
-        while let Some(msg) = self.bus.recv_timeout(deadline - Instant::now()) {
-            self.dispatch(msg);
+        while let Some(msg) = self.context.take_posted().or_else(|| self.bus.try_recv()) {
+            self.dispatch(msg)?;
         }
+
+        Ok(())
+    }
+
+    /// Queues an event to be handled before those waiting in the bus.
+    pub fn post(&mut self, msg: Message) {
+        self.context.post(msg);
+    }
+
+    /// Sets the source of the current time for timers and frame limits.
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.context.set_clock(clock);
+    }
+
+    /// Sets the handler for errors returned by components.
+    pub fn set_error_handler(&mut self, handler: ErrorHandler) {
+        self.config.on_error = Some(handler);
+    }
+
+    /// Sets the terminal capabilities granted by the driver.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.context.set_capabilities(capabilities);
     }
 
     pub fn should_exit(&self) -> bool {
         self.context.shutdown_requested()
     }
 
-    fn dispatch(&mut self, msg: Message) {
+    /// Takes the closures given to [`EventContext::suspend`](crate::EventContext::suspend).
+    pub fn take_suspensions(&mut self) -> Vec<Box<dyn FnOnce()>> {
+        self.context.take_suspensions()
+    }
+
+    /// Requests the interface to be drawn on the next draw.
+    pub fn request_redraw(&mut self) {
+        self.context.request_redraw();
+    }
+
+    /// Takes the value given to [`EventContext::exit_with`](crate::EventContext::exit_with).
+    pub fn take_exit_value(&mut self) -> Option<Box<dyn Any>> {
+        self.context.take_exit_value()
+    }
+
+    fn post_tick(&mut self) {
+        if self.context.tick_requested() {
+            self.context.post(Tick {});
+            self.context.clear_tick_request();
+        }
+    }
+
+    /// Returns when the runtime must stop waiting for events, if ever.
+    fn wake_deadline(&self) -> Option<Instant> {
+        let frame = if self.context.redraw_requested() {
+            self.next_frame()
+        } else {
+            None
+        };
+
+        [self.context.timers().next_deadline(), frame]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// Returns the earliest time the next frame may be drawn, if limited.
+    fn next_frame(&self) -> Option<Instant> {
+        Some(self.last_draw? + self.config.frame_interval?)
+    }
+
+    /// Dispatches a message to the node tree and commits the resulting actions.
+    pub fn dispatch(&mut self, mut msg: Message) -> Result<(), RuntimeError> {
+        if let Some(event) = msg.downcast_mut::<MouseDown>() {
+            event.clicks = self
+                .clicks
+                .register(self.context.now(), event.x, event.y, event.button);
+        }
+
+        if msg.is::<Resize>() {
+            self.context.request_redraw();
+        }
+
         let dispatch = Dispatch::new(&mut self.arena, &mut self.context);
         msg.dispatch(dispatch);
         self.commit();
+        self.report()
+    }
+
+    fn fire_timers(&mut self) -> Result<(), RuntimeError> {
+        let now = self.context.now();
+
+        for timer in self.context.timers_mut().fire(now) {
+            self.dispatch(Message::new(timer))?;
+        }
+
+        Ok(())
+    }
+
+    /// Passes errors returned by components to the error handler, or returns the first one.
+    fn report(&mut self) -> Result<(), RuntimeError> {
+        let mut errors = self.arena.take_errors().into_iter();
+
+        match &mut self.config.on_error {
+            Some(handler) => errors.for_each(handler),
+            None => {
+                if let Some(error) = errors.next() {
+                    return Err(error);
+                }
+            }
+        }
+
+        Ok(())
     }
 
     fn commit(&mut self) {
         for cmd in self.context.drain_commands() {
             cmd.reduce(&mut self.context, &mut self.arena);
         }
+
+        let writes = state::take_writes();
+
+        if !writes.is_empty() {
+            self.context.request_redraw();
+        }
+
+        let recovered = self.arena.recover();
+        let recomposed = self.arena.recompose(&writes);
+
+        if recomposed {
+            // Writes made while composing must not trigger another composition
+            state::take_writes();
+        }
+
+        if recovered || recomposed {
+            self.context.request_redraw();
+            self.prune();
+        }
+    }
+
+    /// Releases resources held for nodes which were removed from the tree.
+    fn prune(&mut self) {
+        if let Some(id) = self.context.focused()
+            && self.arena.get(id).is_none()
+        {
+            Command::ResignFocus(id).reduce(&mut self.context, &mut self.arena);
+        }
+
+        self.context
+            .timers_mut()
+            .retain(|id| self.arena.get(id).is_some());
+
+        #[cfg(feature = "tokio")]
+        self.context
+            .executor_mut()
+            .retain(|id| self.arena.get(id).is_some());
     }
 }
@@ -1,17 +1,74 @@
-use core::cell::{Ref, RefMut};
+use core::{
+    any::Any,
+    cell::{Cell, Ref, RefCell, RefMut},
+};
+use std::collections::HashSet;
 
 use generational_box::{
-    GenerationalBox, GenerationalRef, GenerationalRefMut, Owner, UnsyncStorage,
+    GenerationalBox, GenerationalBoxId, GenerationalRef, GenerationalRefMut, Owner, UnsyncStorage,
 };
 
+pub type StateId = GenerationalBoxId;
+
+thread_local! {
+    static TRACKER: RefCell<Tracker> = RefCell::default();
+}
+
+/// Records state accesses on the runtime thread.
+#[derive(Default)]
+struct Tracker {
+    reads: Option<Vec<StateId>>,
+    writes: HashSet<StateId>,
+}
+
+/// Runs a closure and returns the states it read.
+pub fn track_reads<R>(f: impl FnOnce() -> R) -> (R, Vec<StateId>) {
+    let outer = TRACKER.with_borrow_mut(|t| t.reads.replace(Vec::new()));
+    let value = f();
+    let reads = TRACKER.with_borrow_mut(|t| core::mem::replace(&mut t.reads, outer));
+    (value, reads.unwrap_or_default())
+}
+
+/// Returns the states written since the last call.
+pub fn take_writes() -> HashSet<StateId> {
+    TRACKER.with_borrow_mut(|t| core::mem::take(&mut t.writes))
+}
+
 #[derive(Default)]
 pub struct StateStore {
     inner: Owner<UnsyncStorage>,
+    slots: RefCell<Vec<Box<dyn Any>>>,
+    cursor: Cell<usize>,
 }
 
 impl StateStore {
+    /// Returns the state in the next slot, initializing it with the given value if necessary.
+    ///
+    /// Slots are assigned in call order, so a rebuilt component receives the same states as long
+    /// as it initializes them in the same order.
     pub fn insert<T: 'static>(&self, value: T) -> State<T> {
-        State::new(self.inner.insert(value))
+        let idx = self.cursor.replace(self.cursor.get() + 1);
+        let mut slots = self.slots.borrow_mut();
+
+        if let Some(state) = slots.get(idx).and_then(|s| s.downcast_ref::<State<T>>()) {
+            return *state;
+        }
+
+        let state = State::new(self.inner.insert(value));
+
+        if idx < slots.len() {
+            slots[idx] = Box::new(state);
+        } else {
+            slots.push(Box::new(state));
+        }
+
+        state
+    }
+
+    /// Rewinds the slot cursor so that states can be claimed again by a rebuilt component.
+    pub fn rewind(self) -> Self {
+        self.cursor.set(0);
+        self
     }
 }
 
@@ -35,6 +92,7 @@ impl<T> Copy for State<T> {}
 impl<T> State<T> {
     /// Returns a reference to the inner value.
     pub fn read(&self) -> GenerationalRef<Ref<'_, T>> {
+        self.track_read();
         self.inner.read()
     }
 
@@ -43,6 +101,7 @@ impl<T> State<T> {
     /// It is recommended to use [`State::set`] instead if you do not have a reason for using this
     /// function.
     pub fn write(&mut self) -> GenerationalRefMut<RefMut<'_, T>> {
+        self.track_write();
         self.inner.write()
     }
 
@@ -54,10 +113,28 @@ impl<T> State<T> {
     where
         F: FnOnce(&mut T),
     {
+        self.track_write();
         write(&mut self.inner.write());
     }
 
     pub(crate) fn new(inner: GenerationalBox<T>) -> Self {
         Self { inner }
     }
+
+    fn track_read(&self) {
+        let id = self.inner.id();
+
+        TRACKER.with_borrow_mut(|t| {
+            if let Some(reads) = &mut t.reads
+                && !reads.contains(&id)
+            {
+                reads.push(id);
+            }
+        });
+    }
+
+    fn track_write(&self) {
+        let id = self.inner.id();
+        TRACKER.with_borrow_mut(|t| t.writes.insert(id));
+    }
 }
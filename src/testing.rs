@@ -0,0 +1,332 @@
+//! Utilities for testing components without a terminal.
+
+use core::{fmt::Write, time::Duration};
+use std::{env, fs, path::Path};
+
+use ratatui::buffer::{Buffer, Cell};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    clock::Clock,
+    component::{Component, Factory},
+    drivers::{Driver, TestDriver},
+    errors::RuntimeError,
+    input::Input,
+    runtime::{Runtime, RuntimeConfig},
+    transport::{AppHandle, MessageBus},
+};
+
+/// A headless application for testing components.
+///
+/// Unlike [`launch`](crate::launch), nothing happens in the background. Inputs are queued until
+/// [`TestApp::update`] is called, and the interface is only drawn on [`TestApp::draw`].
+/// Closures given to [`EventContext::suspend`](crate::EventContext::suspend) run at the end of an
+/// update.
+///
+/// Time does not pass on its own either. It is moved forward with [`TestApp::advance`], after
+/// which timers that became due fire on the next update.
+///
+/// # Example
+///
+/// ```rust
+/// use ratatui::style::Style;
+/// use vtui::{events::*, prelude::*, testing::TestApp, Input};
+///
+/// #[component]
+/// fn Counter(c: Component) -> Node {
+///     let mut count = c.state(0);
+///
+///     c.draw(move |canvas| {
+///         canvas.text(0, 0, format!("{}", count.read()), Style::default());
+///     });
+///
+///     c.listen::<KeyPress>(move |_| count.set(|count| *count += 1));
+///
+///     c.compose(|_| {})
+/// }
+///
+/// let mut app = TestApp::new(Counter, 10, 1);
+///
+/// app.input(Input::KeyPress {
+///     key: KeyCode::Char('+'),
+///     modifiers: KeyModifiers::empty(),
+/// });
+/// app.step().unwrap();
+///
+/// assert_eq!(app.buffer(), &ratatui::buffer::Buffer::with_lines(["1         "]));
+/// ```
+pub struct TestApp {
+    runtime: Runtime,
+    driver: TestDriver,
+    handle: AppHandle,
+    inputs: Vec<Input>,
+    clock: Clock,
+}
+
+impl TestApp {
+    /// Mounts the root component on a virtual terminal of the given size.
+    pub fn new(app: Factory, width: u16, height: u16) -> Self {
+        let node = app(Component::new(), ());
+        let bus = MessageBus::default();
+        let handle = AppHandle::from(bus.handle().clone());
+        let mut runtime = Runtime::new(node, bus, RuntimeConfig::default());
+        let driver = TestDriver::new(width, height);
+        let clock = Clock::manual();
+        runtime.set_capabilities(driver.capabilities());
+        runtime.set_clock(clock.clone());
+
+        Self {
+            runtime,
+            driver,
+            handle,
+            inputs: Vec::new(),
+            clock,
+        }
+    }
+
+    /// Queues an input as if it came from the terminal.
+    pub fn input(&mut self, input: Input) -> &mut Self {
+        self.inputs.push(input);
+        self
+    }
+
+    /// Resizes the virtual terminal and queues the matching [`Resize`](crate::events::Resize)
+    /// event.
+    pub fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+        self.driver.backend_mut().resize(width, height);
+        self.input(Input::Resize { width, height })
+    }
+
+    /// Moves the clock of the application forward.
+    ///
+    /// Timers which became due fire on the next update. An interval fires at most once per
+    /// update, however far the clock moved.
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        self.clock.advance(duration);
+        self
+    }
+
+    /// Sets a handler for errors returned by listeners and render functions.
+    ///
+    /// Without a handler, [`TestApp::update`] and [`TestApp::draw`] return the first error, like
+    /// [`LaunchBuilder::on_error`](crate::LaunchBuilder::on_error).
+    pub fn on_error(&mut self, handler: impl FnMut(RuntimeError) + 'static) -> &mut Self {
+        self.runtime.set_error_handler(Box::new(handler));
+        self
+    }
+
+    /// Returns a handle for sending events to the application.
+    pub fn handle(&self) -> AppHandle {
+        self.handle.clone()
+    }
+
+    /// Handles queued inputs, followed by due timers and events sent through a handle.
+    ///
+    /// Suspending has no effect on the virtual terminal, but the interface is drawn in full on
+    /// the next draw.
+    pub fn update(&mut self) -> Result<(), RuntimeError> {
+        for input in core::mem::take(&mut self.inputs) {
+            self.runtime.dispatch(input.into_message())?;
+        }
+
+        self.runtime.update_pending()?;
+
+        let suspensions = self.runtime.take_suspensions();
+
+        if !suspensions.is_empty() {
+            suspensions.into_iter().for_each(|f| f());
+            self.runtime.request_redraw();
+        }
+
+        Ok(())
+    }
+
+    /// Draws the interface if anything changed since the last draw.
+    pub fn draw(&mut self) -> Result<(), RuntimeError> {
+        self.runtime.draw(&mut self.driver)
+    }
+
+    /// Handles all queued events, then draws the interface.
+    pub fn step(&mut self) -> Result<(), RuntimeError> {
+        self.update()?;
+        self.draw()
+    }
+
+    /// Returns the contents of the virtual terminal as of the last draw.
+    pub fn buffer(&self) -> &Buffer {
+        self.driver.backend().buffer()
+    }
+
+    /// Determines if a component requested the application to shut down.
+    pub fn should_exit(&self) -> bool {
+        self.runtime.should_exit()
+    }
+
+    /// Takes the value the application exited with, if it is of the given type.
+    pub fn take_exit_value<T: 'static>(&mut self) -> Option<T> {
+        let value = self.runtime.take_exit_value()?;
+        value.downcast().ok().map(|value| *value)
+    }
+
+    /// Returns the symbols of the virtual terminal as text, one line per row.
+    ///
+    /// Cells hidden by multi-width symbols are left out.
+    pub fn snapshot(&self) -> String {
+        let mut snapshot = String::new();
+
+        for (x, _, cell) in visible_cells(self.buffer()) {
+            if x == 0 && !snapshot.is_empty() {
+                snapshot.push('\n');
+            }
+            snapshot.push_str(cell.symbol());
+        }
+
+        snapshot.push('\n');
+        snapshot
+    }
+
+    /// Returns the symbols of the virtual terminal followed by its styles.
+    ///
+    /// Styles are listed at each cell whose style differs from the previous visible cell.
+    pub fn styled_snapshot(&self) -> String {
+        let mut snapshot = self.snapshot();
+        let mut last = None;
+
+        snapshot.push_str("---\n");
+
+        for (x, y, cell) in visible_cells(self.buffer()) {
+            let style = (cell.fg, cell.bg, cell.modifier);
+
+            if last != Some(style) {
+                let _ = writeln!(
+                    snapshot,
+                    "{x},{y} fg={:?} bg={:?} modifier={:?}",
+                    cell.fg, cell.bg, cell.modifier
+                );
+                last = Some(style);
+            }
+        }
+
+        snapshot
+    }
+}
+
+/// Returns the cells of a buffer with their positions, leaving out cells hidden by multi-width
+/// symbols.
+fn visible_cells(buffer: &Buffer) -> impl Iterator<Item = (usize, usize, &Cell)> {
+    let width = buffer.area.width.max(1) as usize;
+    let mut skip = 0;
+
+    buffer
+        .content
+        .iter()
+        .enumerate()
+        .filter_map(move |(i, cell)| {
+            let (x, y) = (i % width, i / width);
+
+            if x == 0 {
+                skip = 0;
+            }
+
+            let hidden = skip > 0;
+            skip = skip.max(cell.symbol().width()).saturating_sub(1);
+
+            (!hidden).then_some((x, y, cell))
+        })
+}
+
+/// Compares a snapshot against the contents of a file.
+///
+/// If the `VTUI_UPDATE_SNAPSHOTS` environment variable is set, the file is written instead.
+/// Prefer [`assert_snapshot!`](crate::assert_snapshot), which resolves the path from a name.
+///
+/// # Panics
+///
+/// Panics if the file is missing or its contents differ from the snapshot.
+#[track_caller]
+pub fn assert_snapshot(path: impl AsRef<Path>, snapshot: impl AsRef<str>) {
+    let path = path.as_ref();
+    let actual = snapshot.as_ref();
+
+    if env::var_os("VTUI_UPDATE_SNAPSHOTS").is_some() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).expect("failed to create snapshot directory");
+        }
+        fs::write(path, actual).expect("failed to write snapshot");
+        return;
+    }
+
+    let Ok(expected) = fs::read_to_string(path) else {
+        panic!(
+            "snapshot {} does not exist, run with VTUI_UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        );
+    };
+
+    if expected != actual {
+        panic!(
+            "snapshot {} does not match, run with VTUI_UPDATE_SNAPSHOTS=1 to update it\n{}",
+            path.display(),
+            diff(&expected, actual)
+        );
+    }
+}
+
+/// Formats a line diff between two snapshots.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut diff = String::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(a), Some(b)) if a == b => {
+                let _ = writeln!(diff, " {a}");
+            }
+            (a, b) => {
+                if let Some(a) = a {
+                    let _ = writeln!(diff, "-{a}");
+                }
+                if let Some(b) = b {
+                    let _ = writeln!(diff, "+{b}");
+                }
+            }
+        }
+    }
+
+    diff
+}
+
+/// Asserts that a snapshot matches the file stored under `tests/snapshots/<name>.snap`.
+///
+/// The path is relative to the manifest directory of the crate being tested. Set the
+/// `VTUI_UPDATE_SNAPSHOTS` environment variable to create or update snapshot files.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use vtui::{assert_snapshot, prelude::*, testing::TestApp};
+///
+/// #[component]
+/// fn App(c: Component) -> Node {
+///     c.compose(|_| {})
+/// }
+///
+/// let mut app = TestApp::new(App, 20, 5);
+/// app.step().unwrap();
+///
+/// assert_snapshot!("app", app.snapshot());
+/// assert_snapshot!("app_styled", app.styled_snapshot());
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $snapshot:expr $(,)?) => {
+        $crate::testing::assert_snapshot(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join(format!("{}.snap", $name)),
+            $snapshot,
+        )
+    };
+}
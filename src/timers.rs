@@ -0,0 +1,138 @@
+use alloc::rc::Rc;
+use core::{cell::Cell, time::Duration};
+use std::time::Instant;
+
+use crate::{arena::NodeId, events::Timer};
+
+/// Identifies a timer created by a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+/// A handle to a pending timer.
+///
+/// Returned by [`EventContext::set_timeout`](crate::EventContext::set_timeout) and
+/// [`EventContext::set_interval`](crate::EventContext::set_interval).
+#[derive(Debug, Clone)]
+pub struct TimerHandle {
+    id: TimerId,
+    /// Set once the timer can no longer fire.
+    done: Rc<Cell<bool>>,
+}
+
+impl TimerHandle {
+    /// Returns the id carried by [`Timer`] events of this timer.
+    pub fn id(&self) -> TimerId {
+        self.id
+    }
+
+    /// Cancels the timer.
+    ///
+    /// Has no effect if the timer already fired or was cancelled.
+    pub fn cancel(&self) {
+        self.done.set(true);
+    }
+
+    /// Determines if the timer may still fire.
+    ///
+    /// Returns `false` once a timeout fired, or once the timer was cancelled, including by the
+    /// removal of its component.
+    pub fn is_pending(&self) -> bool {
+        !self.done.get()
+    }
+}
+
+/// Schedules timers owned by nodes.
+#[derive(Default)]
+pub struct Timers {
+    next_id: u64,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    id: TimerId,
+    node: NodeId,
+    deadline: Instant,
+    interval: Option<Duration>,
+    done: Rc<Cell<bool>>,
+}
+
+impl Timers {
+    /// The shortest period of an interval, which keeps a zero period from firing continuously.
+    pub const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+    /// Schedules a timer which fires `delay` after `now`, and then every `interval` if given.
+    pub fn schedule(
+        &mut self,
+        node: NodeId,
+        now: Instant,
+        delay: Duration,
+        interval: Option<Duration>,
+    ) -> TimerHandle {
+        let id = TimerId(self.next_id);
+        let done = Rc::new(Cell::new(false));
+        self.next_id += 1;
+
+        self.entries.push(Entry {
+            id,
+            node,
+            deadline: now + delay,
+            interval,
+            done: done.clone(),
+        });
+
+        TimerHandle { id, done }
+    }
+
+    /// Returns the earliest deadline among pending timers.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.done.get())
+            .map(|entry| entry.deadline)
+            .min()
+    }
+
+    /// Returns the events of timers which are due, rescheduling intervals.
+    pub fn fire(&mut self, now: Instant) -> Vec<Timer> {
+        let mut fired = Vec::new();
+
+        self.entries.retain_mut(|entry| {
+            if entry.done.get() {
+                return false;
+            }
+
+            if entry.deadline > now {
+                return true;
+            }
+
+            fired.push(Timer::new(entry.node, entry.id));
+
+            match entry.interval {
+                Some(interval) => {
+                    // Skip missed intervals rather than firing them in a burst, keeping the phase
+                    let late = (now - entry.deadline).as_nanos() % interval.as_nanos();
+                    entry.deadline = now + interval - Duration::from_nanos(late as u64);
+                    true
+                }
+                None => {
+                    entry.done.set(true);
+                    false
+                }
+            }
+        });
+
+        fired
+    }
+
+    /// Cancels the timers of nodes which no longer exist.
+    pub fn retain(&mut self, mut exists: impl FnMut(NodeId) -> bool) {
+        self.entries.retain(|entry| {
+            if exists(entry.node) {
+                return true;
+            }
+
+            entry.done.set(true);
+            false
+        });
+    }
+}
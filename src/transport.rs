@@ -1,4 +1,5 @@
-use core::{any::Any, time::Duration};
+use core::any::Any;
+use std::time::Instant;
 
 use crate::{
     arena::{Arena, NodeId},
@@ -7,18 +8,52 @@ use crate::{
     layout::LogicalRect,
 };
 
+/// Determines which components receive an event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Propagation {
+    /// Every component receives the event.
+    ///
+    /// If the event has a target, the target and its ancestors receive it before any other
+    /// component.
+    #[default]
+    Broadcast,
+
+    /// Only the target and its ancestors receive the event, starting from the target.
+    ///
+    /// If the event has no target, no component receives it.
+    Bubble,
+
+    /// Only the target receives the event.
+    ///
+    /// If the event has no target, no component receives it.
+    Direct,
+}
+
+/// A message which can be dispatched to components.
+///
+/// Custom events can be sent to the application through an [`AppHandle`].
 pub trait Event: Any + Send {
-    fn target(&self, _arena: &Arena) -> Option<NodeId> {
+    /// Returns the component this event is aimed at, if any.
+    fn target(&self, _arena: &Arena, _context: &Context) -> Option<NodeId> {
         None
     }
+
+    /// Returns which components receive this event.
+    fn propagation(&self) -> Propagation {
+        Propagation::Broadcast
+    }
 }
 
 pub trait MouseEvent: Event {
     fn coords(&self) -> (u16, u16);
+
+    fn propagation(&self) -> Propagation {
+        Propagation::Broadcast
+    }
 }
 
 impl<E: MouseEvent> Event for E {
-    fn target(&self, arena: &Arena) -> Option<NodeId> {
+    fn target(&self, arena: &Arena, _context: &Context) -> Option<NodeId> {
         let (x, y) = self.coords();
         let cursor = LogicalRect::new(x as i32, y as i32, 1, 1);
 
@@ -30,6 +65,10 @@ impl<E: MouseEvent> Event for E {
 
         None
     }
+
+    fn propagation(&self) -> Propagation {
+        MouseEvent::propagation(self)
+    }
 }
 
 pub struct Message {
@@ -51,6 +90,14 @@ impl Message {
         Self::from(event)
     }
 
+    pub fn is<E: Event>(&self) -> bool {
+        (self.event.as_ref() as &dyn Any).is::<E>()
+    }
+
+    pub fn downcast_mut<E: Event>(&mut self) -> Option<&mut E> {
+        (self.event.as_mut() as &mut dyn Any).downcast_mut()
+    }
+
     pub fn dispatch(self, dispatch: Dispatch<'_>) {
         (self.dispatch)(self, dispatch)
     }
@@ -81,17 +128,17 @@ pub struct MessageBus {
 
 impl Default for MessageBus {
     fn default() -> Self {
-        let (tx, rx) = flume::bounded(Self::DEFAULT_CAPACITY);
-        let tx = MessageSender::from(tx);
-        Self { tx, rx }
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
     }
 }
 
 impl MessageBus {
-    const DEFAULT_CAPACITY: usize = 128;
+    pub const DEFAULT_CAPACITY: usize = 128;
 
-    pub fn new() -> Self {
-        Self::default()
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (tx, rx) = flume::bounded(capacity.max(1));
+        let tx = MessageSender::from(tx);
+        Self { tx, rx }
     }
 
     pub fn handle(&self) -> &MessageSender {
@@ -102,8 +149,12 @@ impl MessageBus {
         self.rx.recv().expect("bus closed unexpectedly")
     }
 
-    pub fn recv_timeout(&self, timeout: Duration) -> Option<Message> {
-        match self.rx.recv_timeout(timeout) {
+    pub fn try_recv(&self) -> Option<Message> {
+        self.rx.try_recv().ok()
+    }
+
+    pub fn recv_deadline(&self, deadline: Instant) -> Option<Message> {
+        match self.rx.recv_deadline(deadline) {
             Ok(msg) => Some(msg),
             Err(flume::RecvTimeoutError::Timeout) => None,
             Err(flume::RecvTimeoutError::Disconnected) => panic!("bus closed unexpectedly"),
@@ -126,4 +177,38 @@ impl MessageSender {
     pub fn send(&self, msg: impl Into<Message>) -> Result<(), SendError> {
         self.tx.send(msg.into()).map_err(|_| SendError)
     }
+
+    #[cfg(feature = "tokio")]
+    pub async fn send_async(&self, msg: impl Into<Message>) -> Result<(), SendError> {
+        self.tx.send_async(msg.into()).await.map_err(|_| SendError)
+    }
+}
+
+/// A handle for sending events to an application from any thread.
+///
+/// Sending an event wakes the runtime loop, which broadcasts it to the node tree like any other
+/// event. Handles can be obtained from [`LaunchBuilder::handle`](crate::LaunchBuilder::handle) or
+/// [`EventContext::handle`](crate::EventContext::handle).
+#[derive(Clone)]
+pub struct AppHandle {
+    tx: MessageSender,
+}
+
+impl From<MessageSender> for AppHandle {
+    fn from(tx: MessageSender) -> Self {
+        Self { tx }
+    }
+}
+
+impl AppHandle {
+    /// Sends an event to the application.
+    ///
+    /// Blocks if the application has too many pending events. Returns [`SendError`] if the
+    /// application has exited.
+    ///
+    /// Sending from a listener can deadlock, as the blocked listener keeps the application from
+    /// handling the pending events. See [`EventContext::handle`](crate::EventContext::handle).
+    pub fn send<E: Event>(&self, event: E) -> Result<(), SendError> {
+        self.tx.send(event)
+    }
 }
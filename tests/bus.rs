@@ -0,0 +1,66 @@
+use core::time::Duration;
+use std::{sync::mpsc, thread};
+
+use ratatui::style::Style;
+use vtui::{Event, Input, events::*, prelude::*, testing::TestApp};
+
+/// Fills the bus without affecting the application.
+struct Noise;
+
+impl Event for Noise {}
+
+/// Counts focus changes and ticks, requesting them on key presses.
+#[component]
+fn App(c: Component) -> Node {
+    let mut count = c.state(0);
+    c.set_focusable(true);
+
+    c.draw(move |canvas| {
+        canvas.text(0, 0, format!("{}", count.read()), Style::default());
+    });
+
+    c.listen::<KeyPress>(|event| match event.key {
+        KeyCode::Char('f') => event.request_focus(),
+        KeyCode::Char('t') => event.request_tick(),
+        _ => {}
+    });
+
+    c.listen::<FocusChanged>(move |_| count.set(|count| *count += 1));
+    c.listen::<Tick>(move |_| count.set(|count| *count += 1));
+
+    c.compose(|_| {})
+}
+
+/// Presses a key while the bus is full, returning the snapshot unless the application hangs.
+fn press_with_full_bus(c: char) -> Option<String> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut app = TestApp::new(App, 1, 1);
+
+        // The bus holds 128 events by default
+        for _ in 0..128 {
+            app.handle().send(Noise).unwrap();
+        }
+
+        app.input(Input::KeyPress {
+            key: KeyCode::Char(c),
+            modifiers: KeyModifiers::empty(),
+        });
+        app.step().unwrap();
+
+        let _ = tx.send(app.snapshot());
+    });
+
+    rx.recv_timeout(Duration::from_secs(5)).ok()
+}
+
+#[test]
+fn focus_changes_do_not_wait_for_a_full_bus() {
+    assert_eq!(press_with_full_bus('f').as_deref(), Some("1\n"));
+}
+
+#[test]
+fn ticks_do_not_wait_for_a_full_bus() {
+    assert_eq!(press_with_full_bus('t').as_deref(), Some("1\n"));
+}
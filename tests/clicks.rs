@@ -0,0 +1,67 @@
+use core::time::Duration;
+use std::cell::RefCell;
+
+use vtui::{Input, events::*, prelude::*, testing::TestApp};
+
+thread_local! {
+    static CLICKS: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+#[component]
+fn App(c: Component) -> Node {
+    c.listen::<MouseDown>(|event| CLICKS.with_borrow_mut(|clicks| clicks.push(event.clicks)));
+    c.compose(|_| {})
+}
+
+fn click(app: &mut TestApp, x: u16, button: MouseButton) {
+    app.input(Input::MouseDown {
+        x,
+        y: 0,
+        button,
+        modifiers: KeyModifiers::empty(),
+    });
+    app.update().unwrap();
+}
+
+/// Clicks after each delay at the given column with the given button, returning the counts.
+fn clicks(sequence: &[(u64, u16, MouseButton)]) -> Vec<u8> {
+    let mut app = TestApp::new(App, 4, 1);
+    app.draw().unwrap();
+
+    for &(delay, x, button) in sequence {
+        app.advance(Duration::from_millis(delay));
+        click(&mut app, x, button);
+    }
+
+    CLICKS.take()
+}
+
+const LEFT: MouseButton = MouseButton::Left;
+
+#[test]
+fn repeated_clicks_are_counted() {
+    let counts = clicks(&[(0, 0, LEFT), (100, 0, LEFT), (500, 0, LEFT)]);
+
+    assert_eq!(counts, [1, 2, 3]);
+}
+
+#[test]
+fn moving_resets_the_count() {
+    let counts = clicks(&[(0, 0, LEFT), (100, 1, LEFT), (100, 1, LEFT)]);
+
+    assert_eq!(counts, [1, 1, 2]);
+}
+
+#[test]
+fn changing_button_resets_the_count() {
+    let counts = clicks(&[(0, 0, LEFT), (100, 0, MouseButton::Right), (100, 0, LEFT)]);
+
+    assert_eq!(counts, [1, 1, 1]);
+}
+
+#[test]
+fn waiting_resets_the_count() {
+    let counts = clicks(&[(0, 0, LEFT), (501, 0, LEFT), (100, 0, LEFT)]);
+
+    assert_eq!(counts, [1, 1, 2]);
+}
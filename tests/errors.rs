@@ -0,0 +1,147 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use ratatui::style::Style;
+use vtui::{Event, RuntimeError, prelude::*, testing::TestApp};
+
+#[component]
+fn Boundary(c: Component) -> Node {
+    c.catch_errors(Fallback);
+    c.compose(|ui| {
+        ui.child(Broken, ());
+    })
+}
+
+#[component]
+fn Broken(c: Component) -> Node {
+    c.draw(|canvas| {
+        canvas.text(0, 0, "half", Style::default());
+        panic!("broken");
+    });
+    c.compose(|_| {})
+}
+
+#[component]
+fn Fallback(c: Component, info: ErrorInfo) -> Node {
+    c.draw(move |canvas| {
+        canvas.text(0, 0, format!("error: {}", info.message), Style::default());
+    });
+    c.compose(|_| {})
+}
+
+#[test]
+fn render_panic_draws_fallback_in_same_draw() {
+    let mut app = TestApp::new(Boundary, 16, 1);
+    app.draw().unwrap();
+
+    assert_eq!(app.snapshot(), "error: broken   \n");
+}
+
+thread_local! {
+    static REMAINING_RAN: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Makes [`Failing`] return an error from its listener.
+struct Fail;
+
+impl Event for Fail {}
+
+/// Makes [`Failing`] return an error from its render function.
+struct FailDraw;
+
+impl Event for FailDraw {}
+
+#[component]
+fn Tree(c: Component) -> Node {
+    c.compose(|ui| {
+        ui.child(Branch, ());
+    })
+}
+
+#[component]
+fn Branch(c: Component) -> Node {
+    c.compose(|ui| {
+        ui.child(Leaf, ());
+        ui.child(Leaf, ());
+        ui.child(Failing, ());
+    })
+}
+
+#[component]
+fn Leaf(c: Component) -> Node {
+    c.compose(|_| {})
+}
+
+#[component]
+fn Failing(c: Component) -> Node {
+    let mut draw_fails = c.state(false);
+
+    c.try_draw(move |_| match *draw_fails.read() {
+        true => Err("render failed".into()),
+        false => Ok(()),
+    });
+
+    c.try_listen::<Fail>(|_| Err("listener failed".into()));
+    c.listen::<Fail>(|_| REMAINING_RAN.set(true));
+    c.listen::<FailDraw>(move |_| draw_fails.set(|fails| *fails = true));
+
+    c.compose(|_| {})
+}
+
+#[test]
+fn listener_errors_are_returned_with_the_component_path() {
+    let mut app = TestApp::new(Tree, 1, 1);
+    app.handle().send(Fail).unwrap();
+
+    let error = app.step().unwrap_err();
+
+    assert!(matches!(&error, RuntimeError::Component { path, .. } if path == "/0/2"));
+    assert_eq!(
+        error.to_string(),
+        "component at /0/2 failed: listener failed"
+    );
+}
+
+#[test]
+fn listener_errors_skip_the_remaining_listeners() {
+    let mut app = TestApp::new(Tree, 1, 1);
+    app.handle().send(Fail).unwrap();
+
+    let _ = app.step();
+
+    assert!(!REMAINING_RAN.get());
+}
+
+#[test]
+fn render_errors_are_returned_from_draw() {
+    let mut app = TestApp::new(Tree, 1, 1);
+    app.handle().send(FailDraw).unwrap();
+    app.update().unwrap();
+
+    let error = app.draw().unwrap_err();
+
+    assert_eq!(error.to_string(), "component at /0/2 failed: render failed");
+}
+
+#[test]
+fn handled_errors_do_not_stop_the_app() {
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let mut app = TestApp::new(Tree, 1, 1);
+
+    let handled = errors.clone();
+    app.on_error(move |error| handled.borrow_mut().push(error.to_string()));
+    app.handle().send(Fail).unwrap();
+    app.handle().send(FailDraw).unwrap();
+
+    app.step().unwrap();
+
+    assert_eq!(
+        *errors.borrow(),
+        [
+            "component at /0/2 failed: listener failed",
+            "component at /0/2 failed: render failed",
+        ]
+    );
+}
@@ -0,0 +1,202 @@
+use core::time::Duration;
+use std::{env, fs, path::PathBuf, process};
+
+use ratatui::style::{Color, Style};
+use vtui::{
+    Event, Input,
+    events::*,
+    prelude::*,
+    testing::{TestApp, assert_snapshot},
+};
+
+fn key(c: char) -> Input {
+    Input::KeyPress {
+        key: KeyCode::Char(c),
+        modifiers: KeyModifiers::empty(),
+    }
+}
+
+/// Shows a number which is changed by keys, events, timers and suspensions.
+#[component]
+fn Counter(c: Component) -> Node {
+    let mut count = c.state(0);
+
+    c.draw(move |canvas| {
+        canvas.text(0, 0, format!("{}", count.read()), Style::default());
+    });
+
+    c.listen::<KeyPress>(move |event| match event.key {
+        KeyCode::Char('+') => count.set(|count| *count += 1),
+        KeyCode::Char('t') => {
+            event.set_timeout(Duration::from_secs(1));
+        }
+        KeyCode::Char('i') => {
+            event.set_interval(Duration::from_secs(1));
+        }
+        KeyCode::Char('s') => event.suspend(move || count.set(|count| *count += 100)),
+        KeyCode::Char('q') => event.exit_with(*count.read()),
+        _ => {}
+    });
+
+    c.listen::<Timer>(move |_| count.set(|count| *count += 1));
+    c.listen::<Add>(move |event| count.set(|count| *count += event.0));
+    c.listen::<Resize>(move |event| count.set(|count| *count = event.width as i32));
+
+    c.compose(|_| {})
+}
+
+struct Add(i32);
+
+impl Event for Add {}
+
+#[test]
+fn inputs_are_handled_on_update() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.input(key('+')).input(key('+'));
+    app.draw().unwrap();
+    assert_eq!(app.snapshot(), "0  \n");
+
+    app.step().unwrap();
+    assert_eq!(app.snapshot(), "2  \n");
+}
+
+#[test]
+fn handle_events_are_handled_on_update() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.handle().send(Add(5)).unwrap();
+    app.step().unwrap();
+
+    assert_eq!(app.snapshot(), "5  \n");
+}
+
+#[test]
+fn resize_changes_buffer_and_sends_event() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.resize(4, 2).step().unwrap();
+
+    assert_eq!(app.snapshot(), "4   \n    \n");
+}
+
+#[test]
+fn timeouts_fire_once_the_clock_is_advanced() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.input(key('t')).step().unwrap();
+    app.advance(Duration::from_millis(999)).step().unwrap();
+    assert_eq!(app.snapshot(), "0  \n");
+
+    app.advance(Duration::from_millis(1)).step().unwrap();
+    assert_eq!(app.snapshot(), "1  \n");
+
+    app.advance(Duration::from_secs(5)).step().unwrap();
+    assert_eq!(app.snapshot(), "1  \n");
+}
+
+#[test]
+fn intervals_fire_every_period() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.input(key('i')).step().unwrap();
+
+    for expected in ["1  \n", "2  \n", "3  \n"] {
+        app.advance(Duration::from_secs(1)).step().unwrap();
+        assert_eq!(app.snapshot(), expected);
+    }
+}
+
+#[test]
+fn time_does_not_pass_on_its_own() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.input(key('t')).step().unwrap();
+    std::thread::sleep(Duration::from_millis(10));
+    app.step().unwrap();
+
+    assert_eq!(app.snapshot(), "0  \n");
+}
+
+#[test]
+fn suspensions_run_on_update() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.input(key('s')).step().unwrap();
+
+    assert_eq!(app.snapshot(), "100\n");
+}
+
+#[test]
+fn exit_value_is_taken_once() {
+    let mut app = TestApp::new(Counter, 3, 1);
+
+    app.input(key('+')).input(key('q')).update().unwrap();
+
+    assert!(app.should_exit());
+    assert_eq!(app.take_exit_value::<String>(), None);
+    assert_eq!(app.take_exit_value::<i32>(), None);
+
+    let mut app = TestApp::new(Counter, 3, 1);
+    app.input(key('q')).update().unwrap();
+
+    assert_eq!(app.take_exit_value::<i32>(), Some(0));
+    assert_eq!(app.take_exit_value::<i32>(), None);
+}
+
+#[component]
+fn Styled(c: Component) -> Node {
+    c.draw(|canvas| {
+        canvas.text(0, 0, "a", Style::default().fg(Color::Red));
+        canvas.text(1, 0, "界b", Style::default());
+    });
+    c.compose(|_| {})
+}
+
+#[test]
+fn snapshots_skip_cells_hidden_by_wide_symbols() {
+    let mut app = TestApp::new(Styled, 5, 1);
+    app.draw().unwrap();
+
+    assert_eq!(app.snapshot(), "a界b \n");
+    assert_eq!(
+        app.styled_snapshot(),
+        "a界b \n\
+         ---\n\
+         0,0 fg=Red bg=Reset modifier=NONE\n\
+         1,0 fg=Reset bg=Reset modifier=NONE\n"
+    );
+}
+
+/// Returns a path in a fresh temporary directory.
+fn snapshot_path(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("vtui-harness-{}-{name}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join("snapshot.snap")
+}
+
+#[test]
+fn matching_snapshot_passes() {
+    let path = snapshot_path("matching");
+    fs::write(&path, "abc\n").unwrap();
+
+    assert_snapshot(&path, "abc\n");
+}
+
+#[test]
+#[should_panic(expected = "does not match")]
+fn differing_snapshot_panics() {
+    let path = snapshot_path("differing");
+    fs::write(&path, "abc\n").unwrap();
+
+    assert_snapshot(&path, "abd\n");
+}
+
+#[test]
+#[should_panic(expected = "does not exist")]
+fn missing_snapshot_panics() {
+    let path = snapshot_path("missing");
+    let _ = fs::remove_file(&path);
+
+    assert_snapshot(&path, "abc\n");
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "termwiz")]
+
+use std::io;
+
+use vtui::{DriverKind, LaunchBuilder, RuntimeError, prelude::*};
+
+#[component]
+fn App(c: Component) -> Node {
+    c.compose(|_| {})
+}
+
+fn termwiz() -> LaunchBuilder {
+    LaunchBuilder::new().driver(DriverKind::Termwiz)
+}
+
+fn assert_unsupported(builder: LaunchBuilder) {
+    let result = builder.launch(App);
+    assert!(
+        matches!(result, Err(RuntimeError::Unsupported(_))),
+        "expected an unsupported option error, got {result:?}"
+    );
+}
+
+#[test]
+fn termwiz_rejects_inline_viewports() {
+    assert_unsupported(termwiz().inline(4));
+}
+
+#[test]
+fn termwiz_rejects_tty_output() {
+    assert_unsupported(termwiz().tty());
+}
+
+#[test]
+fn termwiz_rejects_writer_output() {
+    assert_unsupported(termwiz().writer(io::sink()));
+}
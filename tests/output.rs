@@ -0,0 +1,145 @@
+//! Checks that drawing elsewhere keeps standard output free for the result of the program.
+
+#![cfg(all(unix, feature = "crossterm"))]
+
+use std::{
+    env,
+    fs::File,
+    io::{self, Read, Write},
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::process::CommandExt,
+    },
+    process::{Command, Stdio},
+    ptr,
+};
+
+use vtui::{LaunchBuilder, RuntimeError, events::Tick, prelude::*};
+
+const CHILD: &str = "VTUI_OUTPUT_CHILD";
+const BEGIN: &[u8] = b"<begin>";
+const END: &[u8] = b"<end>";
+
+#[component]
+fn App(c: Component) -> Node {
+    c.listen::<Tick>(|event| event.request_shutdown());
+    c.compose(|_| {})
+}
+
+/// Launches the app in a child process whose controlling terminal is a new pseudo-terminal,
+/// returning what it wrote to standard output while running.
+fn run_in_terminal(test: &str) -> Vec<u8> {
+    let (master, slave) = open_pty();
+    let slave_fd = slave.as_raw_fd();
+
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args(["--exact", test, "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .stdin(Stdio::from(slave.try_clone().unwrap()))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::from(slave));
+
+    // SAFETY: only async-signal-safe functions are called before exec
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setsid() == -1 || libc::ioctl(slave_fd, libc::TIOCSCTTY, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let mut child = command.spawn().unwrap();
+
+    // Keep reading what the app draws so that it never blocks on a full terminal
+    let mut master = File::from(master);
+    std::thread::spawn(move || io::copy(&mut master, &mut io::sink()));
+
+    let mut stdout = Vec::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_end(&mut stdout)
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let begin = find(&stdout, BEGIN).expect("child did not start") + BEGIN.len();
+    let end = find(&stdout, END).expect("child did not finish");
+    stdout[begin..end].to_vec()
+}
+
+fn open_pty() -> (OwnedFd, OwnedFd) {
+    let (mut master, mut slave) = (0, 0);
+    let size = libc::winsize {
+        ws_row: 5,
+        ws_col: 20,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    // SAFETY: the pointers are valid for the duration of the call
+    let result =
+        unsafe { libc::openpty(&mut master, &mut slave, ptr::null_mut(), ptr::null(), &size) };
+    assert_eq!(result, 0, "{}", io::Error::last_os_error());
+
+    // SAFETY: openpty returned two new file descriptors
+    unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Runs the app with standard output marked and checks its result, if running as the child
+/// process.
+fn child(builder: LaunchBuilder, check: impl FnOnce(Result<(), RuntimeError>)) -> bool {
+    if env::var_os(CHILD).is_none() {
+        return false;
+    }
+
+    let mut stdout = io::stdout();
+    stdout.write_all(BEGIN).unwrap();
+    stdout.flush().unwrap();
+
+    builder.handle().send(Tick {}).unwrap();
+    check(builder.launch(App));
+
+    stdout.write_all(END).unwrap();
+    stdout.flush().unwrap();
+    true
+}
+
+#[test]
+fn tty_leaves_stdout_clean() {
+    if child(LaunchBuilder::new().tty(), Result::unwrap) {
+        return;
+    }
+
+    assert_eq!(run_in_terminal("tty_leaves_stdout_clean"), b"");
+}
+
+#[test]
+fn writer_leaves_stdout_clean() {
+    if child(LaunchBuilder::new().writer(io::sink()), Result::unwrap) {
+        return;
+    }
+
+    assert_eq!(run_in_terminal("writer_leaves_stdout_clean"), b"");
+}
+
+#[test]
+fn inline_tty_with_redirected_stdout_is_rejected() {
+    let builder = LaunchBuilder::new().inline(2).tty();
+    let check = |result| assert!(matches!(result, Err(RuntimeError::Unsupported(_))));
+
+    if child(builder, check) {
+        return;
+    }
+
+    assert_eq!(
+        run_in_terminal("inline_tty_with_redirected_stdout_is_rejected"),
+        b""
+    );
+}
@@ -0,0 +1,206 @@
+use std::cell::RefCell;
+
+use vtui::{Event, EventContext, Input, events::*, prelude::*, testing::TestApp};
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(entry: impl Into<String>) {
+    LOG.with_borrow_mut(|log| log.push(entry.into()));
+}
+
+#[derive(Clone)]
+struct Name(&'static str);
+
+impl Props for Name {}
+
+/// Records each listener it reaches, stopping propagation at the listener named by `stop`.
+struct Probe {
+    stop: &'static str,
+}
+
+impl Event for Probe {}
+
+/// Asks the component with the given name to take focus.
+struct Focus(&'static str);
+
+impl Event for Focus {}
+
+#[component]
+fn Root(c: Component) -> Node {
+    listen(&c, "root");
+
+    c.compose(|ui| {
+        ui.child(Child, Name("first"))
+            .measure(Measure::Percent(0.5));
+        ui.child(Child, Name("second"))
+            .measure(Measure::Percent(0.5));
+    })
+}
+
+#[component]
+fn Child(c: Component, name: Name) -> Node {
+    listen(&c, name.0);
+    c.set_focusable(true);
+
+    c.listen::<Focus>(move |event| {
+        if event.0 == name.0 {
+            event.request_focus();
+        }
+    });
+
+    c.compose(|_| {})
+}
+
+fn listen(c: &Component, name: &'static str) {
+    c.listen::<MouseDown>(move |_| log(name));
+    c.listen::<MouseScroll>(move |_| log(name));
+    c.listen::<MouseHover>(move |_| log(name));
+    c.listen::<KeyPress>(move |_| log(name));
+
+    c.listen_capture::<Probe>(move |event| probe(event, format!("capture {name}")));
+    c.listen::<Probe>(move |event| probe(event, format!("bubble {name}")));
+    c.listen::<Probe>(move |_| log(format!("after {name}")));
+}
+
+fn probe(event: &mut EventContext<Probe>, entry: String) {
+    if event.stop == entry {
+        event.stop_propagation();
+    }
+
+    log(entry);
+}
+
+/// Sends an event to a freshly drawn app, returning the listeners which received it.
+fn receivers(setup: &[Focus], input: impl FnOnce(&mut TestApp)) -> Vec<String> {
+    let mut app = TestApp::new(Root, 10, 2);
+    app.draw().unwrap();
+
+    for Focus(name) in setup {
+        app.handle().send(Focus(name)).unwrap();
+    }
+
+    app.update().unwrap();
+
+    LOG.with_borrow_mut(Vec::clear);
+    input(&mut app);
+    app.update().unwrap();
+    LOG.take()
+}
+
+fn input(input: Input) -> impl FnOnce(&mut TestApp) {
+    move |app| {
+        app.input(input);
+    }
+}
+
+fn probe_stopping_at(stop: &'static str) -> Vec<String> {
+    receivers(&[], |app| app.handle().send(Probe { stop }).unwrap())
+}
+
+fn key() -> Input {
+    Input::KeyPress {
+        key: KeyCode::Char('a'),
+        modifiers: KeyModifiers::empty(),
+    }
+}
+
+#[test]
+fn clicks_bubble_to_ancestors_only() {
+    let received = receivers(
+        &[],
+        input(Input::MouseDown {
+            x: 0,
+            y: 0,
+            button: MouseButton::Left,
+            modifiers: KeyModifiers::empty(),
+        }),
+    );
+
+    assert_eq!(received, ["first", "root"]);
+}
+
+#[test]
+fn scrolls_bubble_to_ancestors_only() {
+    let received = receivers(
+        &[],
+        input(Input::MouseScroll {
+            x: 0,
+            y: 1,
+            direction: MouseScrollDirection::Down,
+            modifiers: KeyModifiers::empty(),
+        }),
+    );
+
+    assert_eq!(received, ["second", "root"]);
+}
+
+#[test]
+fn hover_reaches_every_component() {
+    let received = receivers(
+        &[],
+        input(Input::MouseHover {
+            x: 0,
+            y: 0,
+            modifiers: KeyModifiers::empty(),
+        }),
+    );
+
+    assert_eq!(received, ["first", "root", "second"]);
+}
+
+#[test]
+fn capture_runs_from_root_to_leaves_before_bubbling() {
+    assert_eq!(
+        probe_stopping_at(""),
+        [
+            "capture root",
+            "capture first",
+            "capture second",
+            "bubble second",
+            "after second",
+            "bubble first",
+            "after first",
+            "bubble root",
+            "after root",
+        ]
+    );
+}
+
+#[test]
+fn stopping_during_capture_skips_bubbling() {
+    assert_eq!(
+        probe_stopping_at("capture first"),
+        ["capture root", "capture first"]
+    );
+}
+
+#[test]
+fn stopping_runs_remaining_listeners_of_the_component() {
+    assert_eq!(
+        probe_stopping_at("bubble first"),
+        [
+            "capture root",
+            "capture first",
+            "capture second",
+            "bubble second",
+            "after second",
+            "bubble first",
+            "after first",
+        ]
+    );
+}
+
+#[test]
+fn keys_bubble_from_the_focused_component() {
+    assert_eq!(
+        receivers(&[Focus("second")], input(key())),
+        ["second", "root"]
+    );
+}
+
+#[test]
+fn keys_reach_only_the_root_without_focus() {
+    assert_eq!(receivers(&[], input(key())), ["root"]);
+}
@@ -0,0 +1,239 @@
+use std::cell::RefCell;
+
+use ratatui::style::Style;
+use vtui::{Event, Input, events::*, prelude::*, testing::TestApp};
+
+thread_local! {
+    static COMPOSED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Describes a child of [`List`].
+#[derive(Clone, Copy)]
+struct Entry {
+    factory: Factory<Label>,
+    label: char,
+    key: Option<u8>,
+}
+
+fn keyed(label: char) -> Entry {
+    Entry {
+        factory: Count,
+        label,
+        key: Some(label as u8),
+    }
+}
+
+fn unkeyed(factory: Factory<Label>, label: char) -> Entry {
+    Entry {
+        factory,
+        label,
+        key: None,
+    }
+}
+
+/// Increments the count of the children with a label.
+struct Bump(char);
+
+impl Event for Bump {}
+
+/// Replaces the children of [`List`].
+struct Entries(Vec<Entry>);
+
+impl Event for Entries {}
+
+#[derive(Clone)]
+struct Label(char);
+
+impl Props for Label {}
+
+#[component]
+fn List(c: Component) -> Node {
+    let mut entries = c.state(Vec::<Entry>::new());
+
+    c.listen::<Entries>(move |event| entries.set(|entries| *entries = event.0.clone()));
+
+    c.compose(move |ui| {
+        for entry in entries.read().iter() {
+            let mut child = ui.child(entry.factory, Label(entry.label));
+            child.measure(Measure::Exact(1));
+
+            if let Some(key) = entry.key {
+                child.key(key);
+            }
+        }
+    })
+}
+
+/// Counts the [`Bump`]s of its label.
+#[component]
+fn Count(c: Component, label: Label) -> Node {
+    let mut count = c.state(0);
+
+    c.draw(move |canvas| {
+        canvas.text(
+            0,
+            0,
+            format!("{}{}", label.0, count.read()),
+            Style::default(),
+        );
+    });
+
+    c.listen::<Bump>(move |event| {
+        if event.0 == label.0 {
+            count.set(|count| *count += 1);
+        }
+    });
+
+    c.compose(|_| {})
+}
+
+/// Like [`Count`], but marked so that a change of factory is visible.
+#[component]
+fn OtherCount(c: Component, label: Label) -> Node {
+    let mut count = c.state(0);
+
+    c.draw(move |canvas| {
+        let text = format!("{}{}!", label.0, count.read());
+        canvas.text(0, 0, text, Style::default());
+    });
+
+    c.listen::<Bump>(move |event| {
+        if event.0 == label.0 {
+            count.set(|count| *count += 1);
+        }
+    });
+
+    c.compose(|_| {})
+}
+
+fn list() -> TestApp {
+    TestApp::new(List, 3, 4)
+}
+
+fn set(app: &mut TestApp, entries: &[Entry]) -> String {
+    app.handle().send(Entries(entries.to_vec())).unwrap();
+    app.step().unwrap();
+    app.snapshot()
+}
+
+fn bump(app: &mut TestApp, labels: &str) -> String {
+    for label in labels.chars() {
+        app.handle().send(Bump(label)).unwrap();
+    }
+
+    app.step().unwrap();
+    app.snapshot()
+}
+
+#[test]
+fn keyed_children_keep_state_when_reordered() {
+    let mut app = list();
+
+    set(&mut app, &[keyed('a'), keyed('b'), keyed('c')]);
+    assert_eq!(bump(&mut app, "bcc"), "a0 \nb1 \nc2 \n   \n");
+
+    let snapshot = set(&mut app, &[keyed('c'), keyed('a'), keyed('b')]);
+    assert_eq!(snapshot, "c2 \na0 \nb1 \n   \n");
+}
+
+#[test]
+fn keyed_children_keep_state_across_insertions_and_removals() {
+    let mut app = list();
+
+    set(&mut app, &[keyed('a'), keyed('b'), keyed('c')]);
+    bump(&mut app, "abbccc");
+
+    let snapshot = set(&mut app, &[keyed('a'), keyed('x'), keyed('b'), keyed('c')]);
+    assert_eq!(snapshot, "a1 \nx0 \nb2 \nc3 \n");
+
+    let snapshot = set(&mut app, &[keyed('x'), keyed('c')]);
+    assert_eq!(snapshot, "x0 \nc3 \n   \n   \n");
+
+    let snapshot = set(&mut app, &[keyed('a'), keyed('x'), keyed('c')]);
+    assert_eq!(snapshot, "a0 \nx0 \nc3 \n   \n");
+}
+
+#[test]
+fn unkeyed_children_are_matched_by_position() {
+    let mut app = list();
+
+    set(&mut app, &[unkeyed(Count, 'a'), unkeyed(Count, 'b')]);
+    bump(&mut app, "a");
+
+    let snapshot = set(&mut app, &[unkeyed(Count, 'b'), unkeyed(Count, 'a')]);
+    assert_eq!(snapshot, "b1 \na0 \n   \n   \n");
+
+    let snapshot = set(&mut app, &[unkeyed(Count, 'b')]);
+    assert_eq!(snapshot, "b1 \n   \n   \n   \n");
+}
+
+#[test]
+fn changing_factory_replaces_child() {
+    let mut app = list();
+
+    set(&mut app, &[unkeyed(Count, 'a'), unkeyed(Count, 'b')]);
+    bump(&mut app, "ab");
+
+    let snapshot = set(&mut app, &[unkeyed(OtherCount, 'a'), unkeyed(Count, 'b')]);
+    assert_eq!(snapshot, "a0!\nb1 \n   \n   \n");
+}
+
+#[test]
+fn duplicate_keys_are_reconciled() {
+    let mut app = list();
+    let duplicate = Entry {
+        key: Some(b'a'),
+        ..keyed('b')
+    };
+
+    set(&mut app, &[keyed('a'), duplicate]);
+    assert_eq!(bump(&mut app, "ab"), "a1 \nb1 \n   \n   \n");
+
+    // Only the last child with a key is matched, the rest are created again
+    let snapshot = set(&mut app, &[keyed('a'), duplicate]);
+    assert_eq!(snapshot, "a1 \nb0 \n   \n   \n");
+
+    let snapshot = set(&mut app, &[keyed('c')]);
+    assert_eq!(snapshot, "c0 \n   \n   \n   \n");
+}
+
+#[derive(Clone)]
+struct Shared(State<i32>);
+
+impl Props for Shared {}
+
+#[component]
+fn Parent(c: Component) -> Node {
+    let mut value = c.state(0);
+
+    c.listen::<KeyPress>(move |_| value.set(|value| *value += 1));
+
+    c.compose(move |ui| {
+        let _ = value.read();
+        COMPOSED.with_borrow_mut(|composed| composed.push("parent"));
+        ui.child(Reader, Shared(value));
+    })
+}
+
+#[component]
+fn Reader(c: Component, shared: Shared) -> Node {
+    c.compose(move |_| {
+        let _ = shared.0.read();
+        COMPOSED.with_borrow_mut(|composed| composed.push("reader"));
+    })
+}
+
+#[test]
+fn descendants_of_stale_nodes_compose_once() {
+    let mut app = TestApp::new(Parent, 1, 1);
+    app.draw().unwrap();
+    COMPOSED.with_borrow_mut(Vec::clear);
+
+    app.input(Input::KeyPress {
+        key: KeyCode::Char(' '),
+        modifiers: KeyModifiers::empty(),
+    });
+    app.step().unwrap();
+
+    assert_eq!(COMPOSED.take(), ["parent", "reader"]);
+}
@@ -0,0 +1,84 @@
+use std::cell::Cell;
+
+use vtui::{Event, Input, prelude::*, testing::TestApp};
+
+thread_local! {
+    static RENDERS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Asks [`App`] to perform an action which may affect the interface.
+#[derive(Clone, Copy)]
+enum Action {
+    Nothing,
+    Write,
+    Offset,
+    Focus,
+}
+
+impl Event for Action {}
+
+#[component]
+fn App(c: Component) -> Node {
+    let mut value = c.state(0);
+    c.set_focusable(true);
+
+    c.draw(move |_| {
+        let _ = value.read();
+        RENDERS.set(RENDERS.get() + 1);
+    });
+
+    c.listen::<Action>(move |event| match **event {
+        Action::Nothing => {}
+        Action::Write => value.set(|value| *value += 1),
+        Action::Offset => event.set_offset(1, 0),
+        Action::Focus => event.request_focus(),
+    });
+
+    c.compose(|_| {})
+}
+
+/// Performs an action on a drawn app, returning whether it was drawn again.
+fn redraws(action: impl FnOnce(&mut TestApp)) -> bool {
+    let mut app = TestApp::new(App, 2, 2);
+    app.draw().unwrap();
+
+    let before = RENDERS.get();
+    action(&mut app);
+    app.step().unwrap();
+    RENDERS.get() != before
+}
+
+fn send(action: Action) -> impl FnOnce(&mut TestApp) {
+    move |app| app.handle().send(action).unwrap()
+}
+
+#[test]
+fn events_without_changes_do_not_redraw() {
+    assert!(!redraws(send(Action::Nothing)));
+    assert!(!redraws(|_| {}));
+}
+
+#[test]
+fn state_writes_redraw() {
+    assert!(redraws(send(Action::Write)));
+}
+
+#[test]
+fn offsets_redraw() {
+    assert!(redraws(send(Action::Offset)));
+}
+
+#[test]
+fn focus_changes_redraw() {
+    assert!(redraws(send(Action::Focus)));
+}
+
+#[test]
+fn resizes_redraw() {
+    assert!(redraws(|app| {
+        app.input(Input::Resize {
+            width: 2,
+            height: 2,
+        });
+    }));
+}
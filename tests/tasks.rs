@@ -0,0 +1,124 @@
+#![cfg(feature = "tokio")]
+
+use core::{
+    future,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use std::time::Instant;
+
+use ratatui::style::Style;
+use vtui::{Event, Input, events::*, prelude::*, testing::TestApp};
+
+static DROPPED: AtomicBool = AtomicBool::new(false);
+
+/// Sets [`DROPPED`] when the task holding it is dropped.
+struct DropFlag;
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        DROPPED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Makes [`Waiter`] spawn a task which never finishes.
+struct Spawn;
+
+impl Event for Spawn {}
+
+/// Removes the [`Waiter`] from [`Host`].
+struct Hide;
+
+impl Event for Hide {}
+
+#[component]
+fn App(c: Component) -> Node {
+    let mut result = c.state(0);
+
+    c.draw(move |canvas| {
+        canvas.text(0, 0, format!("{}", result.read()), Style::default());
+    });
+
+    c.listen::<KeyPress>(|event| event.spawn(async { 42 }));
+    c.listen::<TaskOutput<i32>>(move |event| result.set(|result| *result = event.output));
+
+    c.compose(|_| {})
+}
+
+/// Spawns a task and steps the app until its output is drawn.
+fn wait_for_task() {
+    let mut app = TestApp::new(App, 2, 1);
+
+    app.input(Input::KeyPress {
+        key: KeyCode::Enter,
+        modifiers: KeyModifiers::empty(),
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while app.snapshot() != "42\n" {
+        assert!(Instant::now() < deadline, "task output never arrived");
+        app.step().unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn tasks_run_without_a_runtime() {
+    wait_for_task();
+}
+
+#[test]
+fn tasks_run_within_a_current_thread_runtime() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async { wait_for_task() });
+}
+
+#[component]
+fn Waiter(c: Component) -> Node {
+    c.listen::<Spawn>(|event| {
+        event.spawn(async {
+            let _flag = DropFlag;
+            future::pending::<()>().await;
+        })
+    });
+
+    c.compose(|_| {})
+}
+
+/// Shows a [`Waiter`] until hidden.
+#[component]
+fn Host(c: Component) -> Node {
+    let mut shown = c.state(true);
+
+    c.listen::<Hide>(move |_| shown.set(|shown| *shown = false));
+
+    c.compose(move |ui| {
+        if *shown.read() {
+            ui.child(Waiter, ());
+        }
+    })
+}
+
+#[test]
+fn removing_a_component_cancels_its_tasks() {
+    let mut app = TestApp::new(Host, 1, 1);
+    app.handle().send(Spawn).unwrap();
+    app.step().unwrap();
+
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(!DROPPED.load(Ordering::SeqCst));
+
+    app.handle().send(Hide).unwrap();
+    app.step().unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while !DROPPED.load(Ordering::SeqCst) {
+        assert!(Instant::now() < deadline, "task was never cancelled");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,131 @@
+use core::time::Duration;
+use std::cell::RefCell;
+
+use ratatui::style::Style;
+use vtui::{Event, TimerHandle, events::*, prelude::*, testing::TestApp};
+
+thread_local! {
+    static HANDLE: RefCell<Option<TimerHandle>> = const { RefCell::new(None) };
+}
+
+/// Starts an interval with the given period.
+struct Start(Duration);
+
+impl Event for Start {}
+
+/// Cancels the interval through its handle.
+struct Cancel;
+
+impl Event for Cancel {}
+
+/// Removes the [`Ticker`] from [`Host`].
+struct Hide;
+
+impl Event for Hide {}
+
+/// Counts the timer events it receives.
+#[component]
+fn Ticker(c: Component) -> Node {
+    let mut count = c.state(0);
+
+    c.draw(move |canvas| {
+        canvas.text(0, 0, format!("{}", count.read()), Style::default());
+    });
+
+    c.listen::<Start>(move |event| {
+        let period = event.0;
+        HANDLE.set(Some(event.set_interval(period)));
+    });
+
+    c.listen::<Cancel>(|_| HANDLE.with_borrow(|handle| handle.as_ref().unwrap().cancel()));
+
+    c.listen::<Timer>(move |_| count.set(|count| *count += 1));
+
+    c.compose(|_| {})
+}
+
+/// Shows a [`Ticker`] until hidden.
+#[component]
+fn Host(c: Component) -> Node {
+    let mut shown = c.state(true);
+
+    c.listen::<Hide>(move |_| shown.set(|shown| *shown = false));
+
+    c.compose(move |ui| {
+        if *shown.read() {
+            ui.child(Ticker, ());
+        }
+    })
+}
+
+fn pending() -> bool {
+    HANDLE.with_borrow(|handle| handle.as_ref().unwrap().is_pending())
+}
+
+fn start(period: Duration) -> TestApp {
+    let mut app = TestApp::new(Ticker, 1, 1);
+    app.handle().send(Start(period)).unwrap();
+    app.step().unwrap();
+    app
+}
+
+fn advance(app: &mut TestApp, millis: u64) -> String {
+    app.advance(Duration::from_millis(millis)).step().unwrap();
+    app.snapshot()
+}
+
+#[test]
+fn late_intervals_fire_once() {
+    let mut app = start(Duration::from_secs(1));
+
+    assert_eq!(advance(&mut app, 2500), "1\n");
+    assert_eq!(advance(&mut app, 0), "1\n");
+    assert_eq!(advance(&mut app, 0), "1\n");
+}
+
+#[test]
+fn late_intervals_keep_their_phase() {
+    let mut app = start(Duration::from_secs(1));
+
+    advance(&mut app, 2500);
+
+    assert_eq!(advance(&mut app, 499), "1\n");
+    assert_eq!(advance(&mut app, 1), "2\n");
+    assert_eq!(advance(&mut app, 1000), "3\n");
+}
+
+#[test]
+fn zero_periods_are_rounded_up() {
+    let mut app = start(Duration::ZERO);
+
+    assert_eq!(advance(&mut app, 0), "0\n");
+    assert_eq!(advance(&mut app, 1), "1\n");
+    assert_eq!(advance(&mut app, 0), "1\n");
+    assert_eq!(advance(&mut app, 5), "2\n");
+}
+
+#[test]
+fn cancelled_intervals_stop_firing() {
+    let mut app = start(Duration::from_secs(1));
+    assert_eq!(advance(&mut app, 1000), "1\n");
+    assert!(pending());
+
+    app.handle().send(Cancel).unwrap();
+    app.step().unwrap();
+
+    assert_eq!(advance(&mut app, 1000), "1\n");
+    assert!(!pending());
+}
+
+#[test]
+fn removing_a_component_cancels_its_timers() {
+    let mut app = TestApp::new(Host, 1, 1);
+    app.handle().send(Start(Duration::from_secs(1))).unwrap();
+    app.step().unwrap();
+    assert!(pending());
+
+    app.handle().send(Hide).unwrap();
+    app.step().unwrap();
+
+    assert!(!pending());
+}